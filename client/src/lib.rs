@@ -0,0 +1,300 @@
+//! Typed client for integrating the trait contract off-chain.
+//!
+//! Instead of writing the XDR of invocations by hand, build them with a [`TraitClient`]: each method returns a
+//! [`Call`] holding the [`Invocation`] to be submitted and decoding its result into the contract's types.
+//! Events emitted by the contract are decoded into [`TraitEvent`]s.
+//!
+//! ```ignore
+//! let client = TraitClient::new(&env, &contract_id);
+//! let call = client.draw(&caller, &id);
+//! let args = call.invocation.to_sc_vals()?; // the arguments of the host function to invoke
+//! // ... submit the transaction and fetch its result ...
+//! let trait_set = call.decode(&result)?;
+//! ```
+use std::marker::PhantomData;
+
+use soroban_sdk::xdr::ScVal;
+use soroban_sdk::{symbol, Address, Bytes, BytesN, Env, IntoVal, Map, RawVal, Status, Symbol, TryFromVal, Vec};
+
+pub use trait_contract::{
+    AssetDescriptor, AssetTrait, AssignmentProof, BreedConfig, Error, Fee, MintSchedule, NewOption, Phase, Problem, RerollConfig,
+    Season, TraitCollection, TraitOptionItem, TraitOptionValue, TraitStats,
+};
+
+mod tests;
+
+/// the trait-set of an id - the value of the option drawn for each trait
+pub type TraitSet = Map<Symbol, TraitOptionValue>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClientError {
+    /// the invocation failed with one of the contract's errors
+    Contract(Error),
+    /// a value could not be converted from or to its XDR representation
+    Conversion,
+}
+
+/// an invocation of one of the contract's functions
+#[derive(Clone, Debug)]
+pub struct Invocation {
+    pub env: Env,
+    pub contract_id: BytesN<32>,
+    pub function: Symbol,
+    pub args: Vec<RawVal>,
+}
+
+impl Invocation {
+    /// the contract id, the function and its arguments as expected by the `InvokeContract` host function
+    pub fn to_sc_vals(&self) -> Result<std::vec::Vec<ScVal>, ClientError> {
+        let mut vals = std::vec![
+            to_sc_val(&self.env, self.contract_id.clone().into_val(&self.env))?,
+            to_sc_val(&self.env, self.function.into_val(&self.env))?,
+        ];
+        for arg in self.args.iter() {
+            vals.push(to_sc_val(&self.env, arg.map_err(|_| ClientError::Conversion)?)?);
+        }
+        Ok(vals)
+    }
+}
+
+/// an [`Invocation`] returning a `T`
+#[derive(Clone, Debug)]
+pub struct Call<T> {
+    pub invocation: Invocation,
+    result: PhantomData<T>,
+}
+
+impl<T: TryFromVal<Env, RawVal>> Call<T> {
+    fn new(client: &TraitClient, function: Symbol, args: Vec<RawVal>) -> Self {
+        Call {
+            invocation: Invocation { env: client.env.clone(), contract_id: client.contract_id.clone(), function, args },
+            result: PhantomData,
+        }
+    }
+
+    /// decodes the result of the invocation - a contract error is returned as [`ClientError::Contract`]
+    pub fn decode(&self, result: &ScVal) -> Result<T, ClientError> {
+        let env = &self.invocation.env;
+        let raw = RawVal::try_from_val(env, result.clone()).map_err(|_| ClientError::Conversion)?;
+        self.decode_raw(raw)
+    }
+
+    /// decodes the result of the invocation when invoked within an [`Env`]
+    pub fn decode_raw(&self, result: RawVal) -> Result<T, ClientError> {
+        if let Ok(status) = Status::try_from(result) {
+            return Err(Error::try_from(status).map_or(ClientError::Conversion, ClientError::Contract));
+        }
+        T::try_from_val(&self.invocation.env, result).map_err(|_| ClientError::Conversion)
+    }
+}
+
+/// builds the invocations of a deployed trait contract
+#[derive(Clone, Debug)]
+pub struct TraitClient {
+    pub env: Env,
+    pub contract_id: BytesN<32>,
+}
+
+impl TraitClient {
+    pub fn new(env: &Env, contract_id: &BytesN<32>) -> Self {
+        TraitClient { env: env.clone(), contract_id: contract_id.clone() }
+    }
+
+    pub fn init(&self, name: &Bytes, size: u32, rerolls: &RerollConfig) -> Call<TraitCollection> {
+        Call::new(self, symbol!("init"), (name.clone(), size, rerolls.clone()).into_val(&self.env))
+    }
+
+    pub fn add_trait(&self, name: &Symbol, desc: &Bytes) -> Call<Vec<AssetTrait>> {
+        Call::new(self, symbol!("add_trait"), (name.clone(), desc.clone()).into_val(&self.env))
+    }
+
+    pub fn add_option(&self, to_trait: &Symbol, option_name: &Symbol, option_value: &TraitOptionValue) -> Call<AssetTrait> {
+        Call::new(self, symbol!("add_option"), (to_trait.clone(), option_name.clone(), option_value.clone()).into_val(&self.env))
+    }
+
+    pub fn set_total(&self, to_trait: &Symbol, option_name: &Symbol, total: u32) -> Call<AssetTrait> {
+        Call::new(self, symbol!("set_total"), (to_trait.clone(), option_name.clone(), total).into_val(&self.env))
+    }
+
+    pub fn finalize(&self) -> Call<bool> {
+        Call::new(self, symbol!("finalize"), Vec::new(&self.env))
+    }
+
+    pub fn preflight(&self) -> Call<Vec<Problem>> {
+        Call::new(self, symbol!("preflight"), Vec::new(&self.env))
+    }
+
+    pub fn set_empty(&self, allowed: bool) -> Call<()> {
+        Call::new(self, symbol!("set_empty"), (allowed,).into_val(&self.env))
+    }
+
+    pub fn expand(&self, extra_size: u32, new_options: &Vec<NewOption>) -> Call<Season> {
+        Call::new(self, symbol!("expand"), (extra_size, new_options.clone()).into_val(&self.env))
+    }
+
+    pub fn seasons(&self) -> Call<Vec<Season>> {
+        Call::new(self, symbol!("seasons"), Vec::new(&self.env))
+    }
+
+    pub fn set_sched(&self, schedule: &MintSchedule) -> Call<MintSchedule> {
+        Call::new(self, symbol!("set_sched"), (schedule.clone(),).into_val(&self.env))
+    }
+
+    pub fn pause(&self) -> Call<()> {
+        Call::new(self, symbol!("pause"), Vec::new(&self.env))
+    }
+
+    pub fn unpause(&self) -> Call<()> {
+        Call::new(self, symbol!("unpause"), Vec::new(&self.env))
+    }
+
+    pub fn is_paused(&self) -> Call<bool> {
+        Call::new(self, symbol!("is_paused"), Vec::new(&self.env))
+    }
+
+    pub fn migrate(&self) -> Call<u32> {
+        Call::new(self, symbol!("migrate"), Vec::new(&self.env))
+    }
+
+    pub fn version(&self) -> Call<u32> {
+        Call::new(self, symbol!("version"), Vec::new(&self.env))
+    }
+
+    pub fn draw(&self, caller: &Address, id: &BytesN<32>) -> Call<TraitSet> {
+        Call::new(self, symbol!("draw"), (caller.clone(), id.clone()).into_val(&self.env))
+    }
+
+    pub fn draw_many(&self, caller: &Address, ids: &Vec<BytesN<32>>) -> Call<Map<BytesN<32>, TraitSet>> {
+        Call::new(self, symbol!("draw_many"), (caller.clone(), ids.clone()).into_val(&self.env))
+    }
+
+    pub fn draw_asset(&self, caller: &Address, code: &Bytes, issuer: &Address) -> Call<TraitSet> {
+        Call::new(self, symbol!("draw_asset"), (caller.clone(), code.clone(), issuer.clone()).into_val(&self.env))
+    }
+
+    pub fn asset_id(&self, code: &Bytes, issuer: &Address) -> Call<BytesN<32>> {
+        Call::new(self, symbol!("asset_id"), (code.clone(), issuer.clone()).into_val(&self.env))
+    }
+
+    pub fn asset(&self, id: &BytesN<32>) -> Call<AssetDescriptor> {
+        Call::new(self, symbol!("asset"), (id.clone(),).into_val(&self.env))
+    }
+
+    pub fn release(&self, id: &BytesN<32>) -> Call<TraitSet> {
+        Call::new(self, symbol!("release"), (id.clone(),).into_val(&self.env))
+    }
+
+    pub fn reroll(&self, id: &BytesN<32>, trait_name: &Symbol) -> Call<TraitSet> {
+        Call::new(self, symbol!("reroll"), (id.clone(), trait_name.clone()).into_val(&self.env))
+    }
+
+    pub fn approve(&self, id: &BytesN<32>, with: &BytesN<32>, trait_name: &Symbol) -> Call<()> {
+        Call::new(self, symbol!("approve"), (id.clone(), with.clone(), trait_name.clone()).into_val(&self.env))
+    }
+
+    pub fn swap_trait(&self, id_a: &BytesN<32>, id_b: &BytesN<32>, trait_name: &Symbol) -> Call<TraitSet> {
+        Call::new(self, symbol!("swap_trait"), (id_a.clone(), id_b.clone(), trait_name.clone()).into_val(&self.env))
+    }
+
+    pub fn set_breed(&self, config: &BreedConfig) -> Call<BreedConfig> {
+        Call::new(self, symbol!("set_breed"), (config.clone(),).into_val(&self.env))
+    }
+
+    pub fn breed(&self, parent_a: &BytesN<32>, parent_b: &BytesN<32>, child_id: &BytesN<32>) -> Call<TraitSet> {
+        Call::new(self, symbol!("breed"), (parent_a.clone(), parent_b.clone(), child_id.clone()).into_val(&self.env))
+    }
+
+    pub fn stats(&self) -> Call<Vec<TraitStats>> {
+        Call::new(self, symbol!("stats"), Vec::new(&self.env))
+    }
+
+    pub fn rarity(&self, id: &BytesN<32>) -> Call<u64> {
+        Call::new(self, symbol!("rarity"), (id.clone(),).into_val(&self.env))
+    }
+
+    pub fn provenance(&self) -> Call<BytesN<32>> {
+        Call::new(self, symbol!("provenance"), Vec::new(&self.env))
+    }
+
+    pub fn rank(&self, id: &BytesN<32>) -> Call<u32> {
+        Call::new(self, symbol!("rank"), (id.clone(),).into_val(&self.env))
+    }
+
+    pub fn verify(&self) -> Call<bool> {
+        Call::new(self, symbol!("verify"), Vec::new(&self.env))
+    }
+
+    pub fn root(&self) -> Call<BytesN<32>> {
+        Call::new(self, symbol!("root"), Vec::new(&self.env))
+    }
+
+    pub fn proof(&self, id: &BytesN<32>) -> Call<AssignmentProof> {
+        Call::new(self, symbol!("proof"), (id.clone(),).into_val(&self.env))
+    }
+}
+
+/// the events emitted by the contract
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraitEvent {
+    Expanded { season: Season },
+    Paused { by: Address },
+    Unpaused { by: Address },
+    Migrated { version: u32 },
+    Released { id: BytesN<32>, trait_set: TraitSet },
+    Rerolled { id: BytesN<32>, trait_name: Symbol, value: TraitOptionValue },
+    Swapped { id: BytesN<32>, trait_name: Symbol, value: TraitOptionValue },
+    Bred { id: BytesN<32>, trait_set: TraitSet },
+    /// the root of the assignments after new trait-sets have been committed
+    Committed { root: BytesN<32> },
+    Provenance { hash: BytesN<32> },
+    /// an option drawn more often than its total by an unversioned contract - found when migrating
+    Overdrawn { id: BytesN<32>, trait_name: Symbol, option_name: Symbol },
+}
+
+impl TraitEvent {
+    /// decodes an event of the contract from its topics and data - [`None`] if it is not an event of the contract
+    pub fn decode(env: &Env, topics: &Vec<RawVal>, data: RawVal) -> Option<TraitEvent> {
+        let topic = |i: u32| topics.get(i).and_then(|r| r.ok());
+        let name = Symbol::try_from_val(env, topic(0)?).ok()?;
+        let id = || BytesN::<32>::try_from_val(env, topic(1)?).ok();
+        let trait_name = || Symbol::try_from_val(env, topic(2)?).ok();
+        Some(if name == symbol!("expanded") {
+            TraitEvent::Expanded { season: Season::try_from_val(env, data).ok()? }
+        } else if name == symbol!("paused") {
+            TraitEvent::Paused { by: Address::try_from_val(env, data).ok()? }
+        } else if name == symbol!("unpaused") {
+            TraitEvent::Unpaused { by: Address::try_from_val(env, data).ok()? }
+        } else if name == symbol!("migrated") {
+            TraitEvent::Migrated { version: u32::try_from_val(env, data).ok()? }
+        } else if name == symbol!("released") {
+            TraitEvent::Released { id: id()?, trait_set: TraitSet::try_from_val(env, data).ok()? }
+        } else if name == symbol!("rerolled") {
+            TraitEvent::Rerolled { id: id()?, trait_name: trait_name()?, value: TraitOptionValue::try_from_val(env, data).ok()? }
+        } else if name == symbol!("swapped") {
+            TraitEvent::Swapped { id: id()?, trait_name: trait_name()?, value: TraitOptionValue::try_from_val(env, data).ok()? }
+        } else if name == symbol!("bred") {
+            TraitEvent::Bred { id: id()?, trait_set: TraitSet::try_from_val(env, data).ok()? }
+        } else if name == symbol!("committed") {
+            TraitEvent::Committed { root: BytesN::<32>::try_from_val(env, data).ok()? }
+        } else if name == symbol!("provenance") {
+            TraitEvent::Provenance { hash: BytesN::<32>::try_from_val(env, data).ok()? }
+        } else if name == symbol!("overdrawn") {
+            TraitEvent::Overdrawn { id: id()?, trait_name: trait_name()?, option_name: Symbol::try_from_val(env, data).ok()? }
+        } else {
+            return None;
+        })
+    }
+
+    /// decodes an event of the contract from the XDR of its topics and data
+    pub fn decode_xdr(env: &Env, topics: &[ScVal], data: &ScVal) -> Option<TraitEvent> {
+        let mut raw_topics = Vec::new(env);
+        for topic in topics {
+            raw_topics.push_back(RawVal::try_from_val(env, topic.clone()).ok()?);
+        }
+        Self::decode(env, &raw_topics, RawVal::try_from_val(env, data.clone()).ok()?)
+    }
+}
+
+fn to_sc_val(env: &Env, val: RawVal) -> Result<ScVal, ClientError> {
+    ScVal::try_from_val(env, val).map_err(|_| ClientError::Conversion)
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use crate::{AssetDescriptor, BreedConfig, Call, ClientError, Error, MintSchedule, RerollConfig, TraitClient, TraitEvent, TraitOptionValue};
+    use soroban_sdk::testutils::{Accounts, Events};
+    use soroban_sdk::xdr::ScVal;
+    use soroban_sdk::{bytes, symbol, testutils, vec, Address, BytesN, Env, RawVal, Status, TryFromVal};
+    use trait_contract::TraitContract;
+
+    /// a client of a finalized collection of 2 with a single option along with the contract's admin
+    fn get_client() -> (TraitClient, Address) {
+        let env = Env::default();
+        let admin = env.accounts().generate();
+        env.set_source_account(&admin);
+        let contract_id = env.register_contract(None, TraitContract);
+        let client = TraitClient::new(&env, &contract_id);
+
+        submit(client.init(&bytes!(&env, 0xff), 2, &RerollConfig::default())).unwrap();
+        submit(client.add_trait(&symbol!("trait_1"), &bytes!(&env, 0xff))).unwrap();
+        submit(client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1))).unwrap();
+        submit(client.set_total(&symbol!("trait_1"), &symbol!("option_1"), 2)).unwrap();
+        assert_eq!(submit(client.preflight()).unwrap().len(), 0);
+        assert!(submit(client.finalize()).unwrap());
+        (client, Address::Account(admin))
+    }
+
+    /// invokes the call within the environment - passing its result or the status it failed with through its XDR
+    /// representation
+    fn submit<T: TryFromVal<Env, RawVal>>(call: Call<T>) -> Result<T, ClientError> {
+        let invocation = &call.invocation;
+        let env = &invocation.env;
+        assert_eq!(invocation.to_sc_vals()?.len() as u32, invocation.args.len() + 2);
+        let result: RawVal = match env.try_invoke_contract::<RawVal, Status>(&invocation.contract_id, &invocation.function, invocation.args.clone()) {
+            Ok(value) => value.unwrap(),
+            Err(status) => status.unwrap().into(),
+        };
+        call.decode(&ScVal::try_from_val(env, result).unwrap())
+    }
+
+    #[test]
+    fn invoke_and_decode() {
+        let (client, admin) = get_client();
+        let env = &client.env;
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(env) as BytesN<32>;
+        let trait_set = submit(client.draw(&admin, &id)).unwrap();
+        assert_eq!(trait_set.get_unchecked(symbol!("trait_1")).unwrap(), TraitOptionValue::Numeric(1));
+
+        let stats = submit(client.stats()).unwrap();
+        assert_eq!(stats.get_unchecked(0).unwrap().issued, 1);
+        // 2/2 in units of 1/1_000
+        assert_eq!(submit(client.rarity(&id)).unwrap(), 1_000);
+        let proof = submit(client.proof(&id)).unwrap();
+        assert_eq!(proof.root, submit(client.root()).unwrap());
+        assert!(submit(client.verify()).unwrap());
+    }
+
+    #[test]
+    fn decode_contract_error() {
+        let (client, _) = get_client();
+        let env = &client.env;
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(env) as BytesN<32>;
+
+        assert_eq!(submit(client.rarity(&id)), Err(ClientError::Contract(Error::NotAssigned)));
+        assert_eq!(submit(client.asset(&id)), Err(ClientError::Contract(Error::NotAssigned)));
+        assert_eq!(
+            submit(client.set_breed(&BreedConfig { cap: 1, mutation_bps: 10_001 })),
+            Err(ClientError::Contract(Error::InvalidBreedConfig)),
+        );
+        // the contract is not changed by failing invocations
+        assert_eq!(submit(client.stats()).unwrap().get_unchecked(0).unwrap().issued, 0);
+    }
+
+    #[test]
+    fn invoke_configuration() {
+        let (client, admin) = get_client();
+        let env = &client.env;
+
+        assert_eq!(submit(client.version()).unwrap(), 2);
+        assert_eq!(submit(client.migrate()).unwrap(), 2);
+        assert!(!submit(client.is_paused()).unwrap());
+        assert_eq!(submit(client.seasons()).unwrap().len(), 0);
+
+        let schedule = MintSchedule { start: 0, end: 1_000, phases: vec![env], max_per_address: 1 };
+        assert_eq!(submit(client.set_sched(&schedule)).unwrap(), schedule);
+        let config = BreedConfig { cap: 1, mutation_bps: 0 };
+        assert_eq!(submit(client.set_breed(&config)).unwrap(), config);
+
+        // "RUSTLING".hex => 5255535446494e47
+        let code = bytes!(env, 0x5255535446494e47);
+        let issuer = Address::Account(env.accounts().generate());
+        let trait_set = submit(client.draw_asset(&admin, &code, &issuer)).unwrap();
+        let id = submit(client.asset_id(&code, &issuer)).unwrap();
+        assert_eq!(submit(client.draw(&admin, &id)).unwrap(), trait_set);
+        assert_eq!(submit(client.asset(&id)).unwrap(), AssetDescriptor { code, issuer });
+    }
+
+    #[test]
+    fn decode_events() {
+        let (client, admin) = get_client();
+        let env = &client.env;
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(env) as BytesN<32>;
+        let trait_set = submit(client.draw(&admin, &id)).unwrap();
+        submit(client.pause()).unwrap();
+        submit(client.unpause()).unwrap();
+        submit(client.release(&id)).unwrap();
+
+        let events: std::vec::Vec<TraitEvent> = env.events().all().iter()
+            .map(|r| r.unwrap())
+            .filter_map(|(_, topics, data)| TraitEvent::decode(env, &topics, data))
+            .collect();
+        assert!(matches!(events[0], TraitEvent::Provenance { .. }));
+        assert!(matches!(events[1], TraitEvent::Committed { .. }));
+        assert_eq!(events[2..4], [
+            TraitEvent::Paused { by: admin.clone() },
+            TraitEvent::Unpaused { by: admin },
+        ]);
+        // releasing empties the id's leaf
+        assert!(matches!(events[4], TraitEvent::Committed { .. }));
+        assert_eq!(events[5], TraitEvent::Released { id, trait_set });
+    }
+}
@@ -0,0 +1,161 @@
+//! Validates a collection definition (see `trait_contract::definition`) and outputs the invocations setting it up.
+//!
+//! By default the `soroban invoke` commands for `init`, `add_trait`, `add_option`, `set_total` (for options with a
+//! quantity) and `finalize` are printed one per line. With `--format bulk` all invocations are printed as a single JSON payload instead.
+use std::{env, fs, process};
+
+use serde_json::{json, Value};
+use soroban_sdk::{Bytes, Env};
+use trait_contract::definition::CollectionDefinition;
+use trait_contract::{AssetTrait, TraitOptionValue};
+
+mod tests;
+
+const USAGE: &str = "usage: trait_loader <definition.json> [--id <contract-id>] [--format shell|bulk]";
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Shell,
+    Bulk,
+}
+
+struct Args {
+    path: String,
+    contract_id: String,
+    format: Format,
+}
+
+/// an invocation of one of the contract's functions with its arguments as accepted by `soroban invoke --arg`
+#[derive(Debug, PartialEq)]
+struct Invocation {
+    function: &'static str,
+    args: Vec<Value>,
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|e| exit(&e));
+    let json = fs::read_to_string(&args.path).unwrap_or_else(|e| exit(&format!("{}: {}", args.path, e)));
+    let definition = CollectionDefinition::from_json(&json).unwrap_or_else(|e| exit(&format!("{}: {}", args.path, e)));
+    let invocations = invocations(&Env::default(), &definition).unwrap_or_else(|e| exit(&format!("{}: {}", args.path, e)));
+
+    match args.format {
+        Format::Shell => {
+            for invocation in &invocations {
+                println!("{}", shell_command(&args.contract_id, invocation));
+            }
+        }
+        Format::Bulk => println!("{}", bulk_payload(&args.contract_id, &invocations)),
+    }
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let mut parsed = Args { path: String::new(), contract_id: "$CID".to_string(), format: Format::Shell };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--id" => parsed.contract_id = args.next().ok_or_else(|| USAGE.to_string())?,
+            "--format" => parsed.format = match args.next().as_deref() {
+                Some("shell") => Format::Shell,
+                Some("bulk") => Format::Bulk,
+                _ => return Err(USAGE.to_string()),
+            },
+            path if parsed.path.is_empty() && !path.starts_with("--") => parsed.path = path.to_string(),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    if parsed.path.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    Ok(parsed)
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
+
+/// validates the definition and returns the invocations setting it up in order
+fn invocations(env: &Env, definition: &CollectionDefinition) -> Result<Vec<Invocation>, String> {
+    definition.validate(env).map_err(|e| e.to_string())?;
+
+    let mut invocations = vec![Invocation {
+        function: "init",
+        args: vec![bytes_arg(definition.name.as_bytes()), json!({"u32": definition.size}), reroll_config_arg()],
+    }];
+    for trait_definition in &definition.traits {
+        let asset_trait: AssetTrait = trait_definition.to_asset_trait(env).unwrap();
+        invocations.push(Invocation {
+            function: "add_trait",
+            args: vec![symbol_arg(&trait_definition.name), bytes_arg(trait_definition.desc.as_bytes())],
+        });
+        for (i, option) in trait_definition.options.iter().enumerate() {
+            let value = asset_trait.options.get_unchecked(i as u32).unwrap().value;
+            invocations.push(Invocation {
+                function: "add_option",
+                args: vec![symbol_arg(&trait_definition.name), symbol_arg(&option.name), value_arg(&value)],
+            });
+            if let Some(quantity) = option.quantity {
+                invocations.push(Invocation {
+                    function: "set_total",
+                    args: vec![symbol_arg(&trait_definition.name), symbol_arg(&option.name), json!({"u32": quantity})],
+                });
+            }
+        }
+    }
+    invocations.push(Invocation { function: "finalize", args: vec![] });
+    Ok(invocations)
+}
+
+fn shell_command(contract_id: &str, invocation: &Invocation) -> String {
+    let mut command = format!("soroban invoke --id {} --fn {}", contract_id, invocation.function);
+    for arg in &invocation.args {
+        command.push_str(&format!(" --arg '{}'", arg));
+    }
+    command
+}
+
+fn bulk_payload(contract_id: &str, invocations: &[Invocation]) -> Value {
+    json!({
+        "contract_id": contract_id,
+        "invocations": invocations.iter()
+            .map(|i| json!({"fn": i.function, "args": i.args}))
+            .collect::<Vec<Value>>(),
+    })
+}
+
+fn hex(bytes: impl IntoIterator<Item = u8>) -> String {
+    bytes.into_iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn bytes_arg(bytes: &[u8]) -> Value {
+    json!({"object": {"bytes": hex(bytes.iter().copied())}})
+}
+
+fn symbol_arg(name: &str) -> Value {
+    json!({"symbol": name})
+}
+
+/// rerolling disabled - see the README
+fn reroll_config_arg() -> Value {
+    json!({"object": {"map": [
+        {"key": {"symbol": "fee"}, "val": {"object": {"vec": [{"symbol": "None"}]}}},
+        {"key": {"symbol": "limit"}, "val": {"u32": 0}},
+    ]}})
+}
+
+fn value_arg(value: &TraitOptionValue) -> Value {
+    let (variant, fields): (&str, Vec<Value>) = match value {
+        TraitOptionValue::None => ("None", vec![]),
+        TraitOptionValue::Numeric(n) => ("Numeric", vec![json!({"u32": n})]),
+        TraitOptionValue::Characters(bytes) => ("Characters", vec![json!({"object": {"bytes": hex(bytes.iter())}})]),
+        TraitOptionValue::Signed(n) => ("Signed", vec![json!({"i32": n})]),
+        TraitOptionValue::Boolean(b) => ("Boolean", vec![json!({"static": b.to_string()})]),
+        TraitOptionValue::Decimal(value, decimals) => ("Decimal", vec![json!({"object": {"i64": value}}), json!({"u32": decimals})]),
+        TraitOptionValue::Color(rgb) => ("Color", vec![json!({"u32": rgb})]),
+        TraitOptionValue::ContentHash(hash) => ("ContentHash", vec![json!({"object": {"bytes": hex(Bytes::from(hash.clone()).iter())}})]),
+        TraitOptionValue::Range(min, max) => ("Range", vec![json!({"u32": min}), json!({"u32": max})]),
+    };
+    let mut vec = vec![json!({"symbol": variant})];
+    vec.extend(fields);
+    json!({"object": {"vec": vec}})
+}
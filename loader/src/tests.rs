@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod tests {
+    use crate::{bulk_payload, invocations, shell_command, value_arg};
+    use serde_json::json;
+    use soroban_sdk::{Env, BytesN};
+    use trait_contract::definition::{CollectionDefinition, DefinitionError};
+    use trait_contract::{Error, TraitOptionValue};
+
+    fn definition(json: serde_json::Value) -> CollectionDefinition {
+        CollectionDefinition::from_json(&json.to_string()).unwrap()
+    }
+
+    fn validate(json: serde_json::Value) -> Result<(), DefinitionError> {
+        definition(json).validate(&Env::default())
+    }
+
+    #[test]
+    fn example_definition() {
+        let json = include_str!("../../simulator/examples/rustlings.json");
+        let definition = CollectionDefinition::from_json(json).unwrap();
+        let invocations = invocations(&Env::default(), &definition).unwrap();
+
+        // init, 3 traits with 5, 5 and 15 options, finalize
+        assert_eq!(invocations.len(), 1 + 3 + 25 + 1);
+        assert_eq!(invocations[0].function, "init");
+        assert_eq!(invocations[1].function, "add_trait");
+        assert_eq!(invocations[29].function, "finalize");
+        // "black".hex => 626c61636b
+        assert_eq!(
+            shell_command("$CID", &invocations[2]),
+            r#"soroban invoke --id $CID --fn add_option --arg '{"symbol":"eyes"}' --arg '{"symbol":"black"}' --arg '{"object":{"vec":[{"symbol":"Characters"},{"object":{"bytes":"626c61636b"}}]}}'"#,
+        );
+        assert_eq!(bulk_payload("$CID", &invocations)["invocations"][29], json!({"fn": "finalize", "args": []}));
+    }
+
+    #[test]
+    fn value_args() {
+        let env = Env::default();
+        assert_eq!(value_arg(&TraitOptionValue::Signed(-5)), json!({"object": {"vec": [{"symbol": "Signed"}, {"i32": -5}]}}));
+        assert_eq!(value_arg(&TraitOptionValue::Boolean(true)), json!({"object": {"vec": [{"symbol": "Boolean"}, {"static": "true"}]}}));
+        assert_eq!(
+            value_arg(&TraitOptionValue::Decimal(12345, 2)),
+            json!({"object": {"vec": [{"symbol": "Decimal"}, {"object": {"i64": 12345}}, {"u32": 2}]}}),
+        );
+        assert_eq!(value_arg(&TraitOptionValue::Range(80, 100)), json!({"object": {"vec": [{"symbol": "Range"}, {"u32": 80}, {"u32": 100}]}}));
+        assert_eq!(
+            value_arg(&TraitOptionValue::ContentHash(BytesN::from_array(&env, &[0xab; 32]))),
+            json!({"object": {"vec": [{"symbol": "ContentHash"}, {"object": {"bytes": "ab".repeat(32)}}]}}),
+        );
+        assert_eq!(value_arg(&TraitOptionValue::None), json!({"object": {"vec": [{"symbol": "None"}]}}));
+    }
+
+    #[test]
+    fn validation() {
+        let option = |name: &str, value: serde_json::Value| json!({"name": name, "value": value});
+        let collection = |size: u32, options: Vec<serde_json::Value>| json!({
+            "name": "test",
+            "size": size,
+            "traits": [{"name": "trait_1", "options": options}],
+        });
+        let error = |error: Error, option_name: Option<&str>| Err(DefinitionError {
+            trait_name: Some("trait_1".to_string()),
+            option_name: option_name.map(|o| o.to_string()),
+            error,
+        });
+
+        assert_eq!(validate(collection(2, vec![option("option_1", json!({"Numeric": 1}))])), Ok(()));
+        // more options than the collection's size
+        assert_eq!(
+            validate(collection(1, vec![option("option_1", json!({"Numeric": 1})), option("option_2", json!({"Numeric": 2}))])),
+            error(Error::TraitNotReady, None),
+        );
+        assert_eq!(validate(collection(1, vec![])), error(Error::TraitNotReady, None));
+        assert_eq!(
+            validate(collection(2, vec![option("option_1", json!({"Numeric": 1})), option("option_1", json!({"Numeric": 2}))])),
+            error(Error::OptionAlreadyExistsOnTrait, Some("option_1")),
+        );
+        assert_eq!(validate(collection(1, vec![option("option_1", json!({"Color": "#1000000"}))])), error(Error::InvalidOptionValue, Some("option_1")));
+        assert_eq!(validate(collection(1, vec![option("option_1", json!({"Range": {"min": 2, "max": 1}}))])), error(Error::InvalidOptionValue, Some("option_1")));
+        // symbols are at most 10 characters
+        assert_eq!(validate(collection(1, vec![option("option_long", json!("None"))])), error(Error::InvalidName, Some("option_long")));
+    }
+
+    #[test]
+    fn validation_of_quantities() {
+        let collection = |size: u32, quantities: [Option<u32>; 2]| json!({
+            "name": "test",
+            "size": size,
+            "traits": [{"name": "trait_1", "options": [
+                {"name": "option_1", "value": {"Numeric": 1}, "quantity": quantities[0]},
+                {"name": "option_2", "value": {"Numeric": 2}, "quantity": quantities[1]},
+            ]}],
+        });
+        let failed = |option_name: Option<&str>| Err(DefinitionError {
+            trait_name: Some("trait_1".to_string()),
+            option_name: option_name.map(|o| o.to_string()),
+            error: Error::OptionDistributionFailed,
+        });
+
+        assert_eq!(validate(collection(5, [Some(2), Some(3)])), Ok(()));
+        assert_eq!(validate(collection(5, [Some(4), None])), Ok(()));
+        // leaving nothing for option_2
+        assert_eq!(validate(collection(5, [Some(5), None])), failed(None));
+        // not adding up to the size
+        assert_eq!(validate(collection(5, [Some(2), Some(2)])), failed(None));
+        assert_eq!(validate(collection(5, [Some(0), None])), failed(Some("option_1")));
+    }
+
+    #[test]
+    fn quantity_invocations() {
+        let json = json!({
+            "name": "test",
+            "size": 5,
+            "traits": [{"name": "trait_1", "options": [
+                {"name": "option_1", "value": {"Numeric": 1}, "quantity": 4},
+                {"name": "option_2", "value": {"Numeric": 2}},
+            ]}],
+        });
+        let invocations = invocations(&Env::default(), &definition(json)).unwrap();
+
+        // init, add_trait, add_option, set_total, add_option, finalize
+        assert_eq!(invocations.len(), 6);
+        assert_eq!(invocations[3].function, "set_total");
+        assert_eq!(invocations[3].args, vec![json!({"symbol": "trait_1"}), json!({"symbol": "option_1"}), json!({"u32": 4})]);
+        assert_eq!(invocations[4].function, "add_option");
+    }
+
+    #[test]
+    fn unknown_field_rejected() {
+        let json = json!({
+            "name": "test",
+            "size": 5,
+            "traits": [{"name": "trait_1", "options": [{"name": "option_1", "value": {"Numeric": 1}, "weight": 5}]}],
+        });
+        let error = CollectionDefinition::from_json(&json.to_string()).unwrap_err();
+        assert!(error.to_string().contains("unknown field `weight`"), "{}", error);
+    }
+
+    #[test]
+    fn validation_of_names() {
+        let collection = |trait_name: &str, option_name: &str| json!({
+            "name": "test",
+            "size": 1,
+            "traits": [{"name": trait_name, "options": [{"name": option_name, "value": "None"}]}],
+        });
+        let error = |trait_name: &str, option_name: Option<&str>, error| Err(DefinitionError {
+            trait_name: Some(trait_name.to_string()),
+            option_name: option_name.map(|o| o.to_string()),
+            error,
+        });
+
+        assert_eq!(validate(collection("", "option_1")), error("", None, Error::MissingName));
+        assert_eq!(validate(collection("trait_1", "")), error("trait_1", Some(""), Error::MissingName));
+        assert_eq!(validate(collection("trait-1", "option_1")), error("trait-1", None, Error::InvalidName));
+        assert_eq!(validate(collection("trait_1", "option 1")), error("trait_1", Some("option 1"), Error::InvalidName));
+    }
+
+    #[test]
+    fn empty_collection() {
+        let not_ready = Err(DefinitionError { trait_name: None, option_name: None, error: Error::CollectionNotReady });
+        let trait_1 = json!({"name": "trait_1", "options": [{"name": "option_1", "value": "None"}]});
+        assert_eq!(validate(json!({"name": "test", "size": 1, "traits": []})), not_ready);
+        assert_eq!(validate(json!({"name": "test", "size": 0, "traits": [trait_1]})), not_ready);
+    }
+
+    #[test]
+    fn duplicate_traits() {
+        let trait_1 = json!({"name": "trait_1", "options": [{"name": "option_1", "value": "None"}]});
+        assert_eq!(
+            validate(json!({"name": "test", "size": 1, "traits": [trait_1, trait_1]})),
+            Err(DefinitionError { trait_name: Some("trait_1".to_string()), option_name: None, error: Error::TraitExists }),
+        );
+    }
+}
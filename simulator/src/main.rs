@@ -0,0 +1,165 @@
+//! Previews a collection before deploying it.
+//!
+//! Loads a collection definition (see `trait_contract::definition`), runs `finalize` and draws the whole
+//! collection against the contract built natively, then prints how often each option has been drawn
+//! along with some sample trait-sets.
+//!
+//! The same definition and seed always result in the same draws.
+use std::{env, fs, process};
+
+use soroban_sdk::{Address, Bytes, BytesN, Env, Map, Symbol, Vec};
+use trait_contract::definition::{to_symbol, CollectionDefinition};
+use trait_contract::testutils::seeded_client;
+use trait_contract::{RerollConfig, TraitContractClient, TraitOptionValue};
+
+mod tests;
+
+const USAGE: &str = "usage: trait_simulator <definition.json> [--seed <seed>] [--samples <count>]";
+/// ids drawn per invocation
+const BATCH: u32 = 50;
+
+struct Args {
+    path: String,
+    seed: u32,
+    samples: u32,
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|e| exit(&e));
+    let json = fs::read_to_string(&args.path).unwrap_or_else(|e| exit(&format!("{}: {}", args.path, e)));
+    let definition = CollectionDefinition::from_json(&json).unwrap_or_else(|e| exit(&format!("{}: {}", args.path, e)));
+
+    let (client, admin) = seeded_client(args.seed);
+    setup(&client, &definition).unwrap_or_else(|e| exit(&e));
+
+    let samples = draw_all(&client, &admin, definition.size, args.samples);
+
+    println!("{} - {} trait-sets drawn with seed {}", definition.name, definition.size, args.seed);
+    client.env.budget().reset_default();
+    let stats = client.stats();
+    for (t, trait_definition) in definition.traits.iter().enumerate() {
+        let trait_stats = stats.get_unchecked(t as u32).unwrap();
+        println!();
+        println!("{}", trait_definition.name);
+        for (o, option_definition) in trait_definition.options.iter().enumerate() {
+            let option = trait_stats.options.get_unchecked(o as u32).unwrap();
+            println!(
+                "  {:<12} {:>8} {:>7.2}%",
+                option_definition.name,
+                option.issued,
+                option.percentage_bps as f64 / 100.0,
+            );
+        }
+    }
+
+    if !samples.is_empty() {
+        println!();
+        println!("samples");
+    }
+    for trait_set in samples {
+        let values: std::vec::Vec<String> = definition.traits.iter()
+            .map(|t| {
+                let value = trait_set.get_unchecked(to_symbol(&t.name).unwrap()).unwrap();
+                format!("{}: {}", t.name, describe(&value))
+            })
+            .collect();
+        println!("  {}", values.join(", "));
+    }
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let mut parsed = Args { path: String::new(), seed: 0, samples: 5 };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => parsed.seed = parse_number(args.next())?,
+            "--samples" => parsed.samples = parse_number(args.next())?,
+            path if parsed.path.is_empty() && !path.starts_with("--") => parsed.path = path.to_string(),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    if parsed.path.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    Ok(parsed)
+}
+
+fn parse_number(arg: Option<String>) -> Result<u32, String> {
+    arg.and_then(|a| a.parse().ok()).ok_or_else(|| USAGE.to_string())
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
+
+/// validates the definition and invokes `init`, `add_trait`, `add_option`, `set_total` and `finalize` as defined
+fn setup(client: &TraitContractClient, definition: &CollectionDefinition) -> Result<(), String> {
+    let env = &client.env;
+    definition.validate(env).map_err(|e| e.to_string())?;
+    client.init(&Bytes::from_slice(env, definition.name.as_bytes()), &definition.size, &RerollConfig::default());
+    for trait_definition in &definition.traits {
+        let asset_trait = trait_definition.to_asset_trait(env).unwrap();
+        client.add_trait(&asset_trait.name, &asset_trait.desc);
+        for option in asset_trait.options.iter().map(|r| r.unwrap()) {
+            client.add_option(&asset_trait.name, &option.name, &option.value);
+            if option.total > 0 {
+                client.set_total(&asset_trait.name, &option.name, &option.total);
+            }
+        }
+    }
+    env.budget().reset_default();
+    client.finalize();
+    Ok(())
+}
+
+/// draws the whole collection in batches of [`BATCH`] ids
+///
+/// returns the trait-sets of the first `samples` ids
+fn draw_all(client: &TraitContractClient, admin: &Address, size: u32, samples: u32) -> std::vec::Vec<Map<Symbol, TraitOptionValue>> {
+    let mut drawn_samples = vec![];
+    for batch in 0..(size + BATCH - 1) / BATCH {
+        let env = &client.env;
+        env.budget().reset_default();
+        let mut ids: Vec<BytesN<32>> = Vec::new(env);
+        for i in batch * BATCH..size.min((batch + 1) * BATCH) {
+            ids.push_back(id(env, i));
+        }
+        let drawn = client.draw_many(admin, &ids);
+        for id in ids.iter().map(|r| r.unwrap()) {
+            if drawn_samples.len() < samples as usize {
+                drawn_samples.push(drawn.get_unchecked(id).unwrap());
+            }
+        }
+    }
+    drawn_samples
+}
+
+/// the i-th drawn id
+fn id(env: &Env, i: u32) -> BytesN<32> {
+    let mut id = [0u8; 32];
+    id[28..].copy_from_slice(&i.to_be_bytes());
+    BytesN::from_array(env, &id)
+}
+
+fn describe(value: &TraitOptionValue) -> String {
+    match value {
+        TraitOptionValue::None => "-".to_string(),
+        TraitOptionValue::Numeric(n) => n.to_string(),
+        TraitOptionValue::Characters(bytes) => String::from_utf8_lossy(&bytes.iter().collect::<std::vec::Vec<u8>>()).into_owned(),
+        TraitOptionValue::Signed(n) => n.to_string(),
+        TraitOptionValue::Boolean(b) => b.to_string(),
+        TraitOptionValue::Decimal(value, decimals) => {
+            let scale = 10i128.pow(*decimals);
+            let (sign, value) = (if *value < 0 { "-" } else { "" }, (*value as i128).abs());
+            if *decimals == 0 {
+                format!("{}{}", sign, value)
+            } else {
+                format!("{}{}.{:0width$}", sign, value / scale, value % scale, width = *decimals as usize)
+            }
+        }
+        TraitOptionValue::Color(rgb) => format!("#{:06x}", rgb),
+        TraitOptionValue::ContentHash(hash) => Bytes::from(hash.clone()).iter().map(|b| format!("{:02x}", b)).collect(),
+        TraitOptionValue::Range(min, max) => format!("{}..={}", min, max),
+    }
+}
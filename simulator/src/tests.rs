@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use crate::{draw_all, setup};
+    use trait_contract::definition::CollectionDefinition;
+    use trait_contract::testutils::seeded_client;
+
+    #[test]
+    fn draw_large_collection() {
+        let json = include_str!("../examples/rustlings.json");
+        let definition = CollectionDefinition { size: 10_000, ..CollectionDefinition::from_json(json).unwrap() };
+        let (client, admin) = seeded_client(42);
+        setup(&client, &definition).unwrap();
+
+        let samples = draw_all(&client, &admin, definition.size, 3);
+        assert_eq!(samples.len(), 3);
+
+        client.env.budget().reset_default();
+        let stats = client.stats();
+        assert_eq!(stats.len(), definition.traits.len() as u32);
+        for trait_stats in stats.iter().map(|r| r.unwrap()) {
+            assert_eq!(trait_stats.issued, definition.size);
+            let issued: u32 = trait_stats.options.iter().map(|r| r.unwrap().issued).sum();
+            assert_eq!(issued, definition.size);
+            assert!(trait_stats.options.iter().map(|r| r.unwrap()).all(|o| o.issued == o.total && o.available == 0));
+        }
+    }
+
+    #[test]
+    fn draw_with_quantities() {
+        let json = r#"{"name": "test", "size": 20, "traits": [{"name": "trait_1", "options": [
+            {"name": "option_1", "value": "None", "quantity": 15},
+            {"name": "option_2", "value": "None"},
+            {"name": "option_3", "value": "None"}
+        ]}]}"#;
+        let definition = CollectionDefinition::from_json(json).unwrap();
+        let (client, admin) = seeded_client(42);
+        setup(&client, &definition).unwrap();
+
+        draw_all(&client, &admin, definition.size, 0);
+        let options = client.stats().get_unchecked(0).unwrap().options;
+        assert_eq!(options.get_unchecked(0).unwrap().issued, 15);
+        let issued: u32 = options.iter().map(|r| r.unwrap().issued).sum();
+        assert_eq!(issued, definition.size);
+    }
+}
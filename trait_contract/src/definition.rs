@@ -0,0 +1,206 @@
+//! Collection definitions to set up a collection off-chain (requires the `std` feature).
+//!
+//! A definition describes the collection the way it is built by `init`, `add_trait`, `add_option` and `set_total`.
+//! Options may state the `quantity` they are to be drawn - it is fixed as the option's total by `set_total`, while
+//! the rest of the collection's size is distributed over the other options randomly on `finalize`.
+//! Definitions with unknown fields are rejected.
+//! ```json
+//! {
+//!   "name": "rustlings",
+//!   "size": 15,
+//!   "traits": [
+//!     {
+//!       "name": "hair",
+//!       "desc": "hair color",
+//!       "options": [
+//!         { "name": "brown", "value": { "Characters": "brown" }, "quantity": 10 },
+//!         { "name": "blonde", "value": { "Characters": "blonde" } }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+use std::string::String;
+use std::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+use soroban_sdk::{Bytes, BytesN, Env, Symbol};
+
+use crate::types::{AssetTrait, Error, TraitOptionItem, TraitOptionValue};
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CollectionDefinition {
+    pub name: String,
+    pub size: u32,
+    pub traits: Vec<TraitDefinition>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TraitDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+    pub options: Vec<OptionDefinition>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct OptionDefinition {
+    pub name: String,
+    pub value: ValueDefinition,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u32>,
+}
+
+/// a definition not meeting the contract's rules along with the trait and option it was found at
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefinitionError {
+    pub trait_name: Option<String>,
+    pub option_name: Option<String>,
+    pub error: Error,
+}
+
+impl std::fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.error)?;
+        if let Some(trait_name) = &self.trait_name {
+            write!(f, " at trait {}", trait_name)?;
+        }
+        if let Some(option_name) = &self.option_name {
+            write!(f, ", option {}", option_name)?;
+        }
+        Ok(())
+    }
+}
+
+/// the [`TraitOptionValue`]s in a human-editable form
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum ValueDefinition {
+    None,
+    Numeric(u32),
+    Characters(String),
+    Signed(i32),
+    Boolean(bool),
+    Decimal { value: i64, decimals: u32 },
+    /// color as "#RRGGBB"
+    Color(String),
+    /// sha256 hash as 64 hex characters
+    ContentHash(String),
+    Range { min: u32, max: u32 },
+}
+
+impl CollectionDefinition {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// checks the definition against the rules the contract applies on `add_trait`, `add_option` and `finalize`
+    ///
+    /// - the size must not be 0 and there must be at least one trait ([`Error::CollectionNotReady`])
+    /// - names must not be empty ([`Error::MissingName`]) and must be valid symbols ([`Error::InvalidName`])
+    /// - trait names must be unique ([`Error::TraitExists`]), option names unique per trait ([`Error::OptionAlreadyExistsOnTrait`])
+    /// - values must be valid ([`Error::InvalidOptionValue`])
+    /// - every trait must be ready to be finalized - see [`AssetTrait::check_is_ready`] ([`Error::TraitNotReady`])
+    /// - quantities must not be 0, must leave at least one for every option without a quantity and must add up to the
+    ///   size if every option of a trait has one - see [`AssetTrait::totals_fit`] ([`Error::OptionDistributionFailed`])
+    pub fn validate(&self, env: &Env) -> Result<(), DefinitionError> {
+        if self.size == 0 || self.traits.is_empty() {
+            return Err(DefinitionError { trait_name: None, option_name: None, error: Error::CollectionNotReady });
+        }
+        for (i, trait_definition) in self.traits.iter().enumerate() {
+            let fail = |option_name: Option<&String>, error| Err(DefinitionError {
+                trait_name: Some(trait_definition.name.clone()),
+                option_name: option_name.cloned(),
+                error,
+            });
+            if self.traits[..i].iter().any(|t| t.name == trait_definition.name) {
+                return fail(None, Error::TraitExists);
+            }
+            let asset_trait = match trait_definition.to_asset_trait(env) {
+                Ok(asset_trait) => asset_trait,
+                Err((option_name, error)) => return fail(option_name.as_ref(), error),
+            };
+            if !asset_trait.check_is_ready(self.size) {
+                return fail(None, Error::TraitNotReady);
+            }
+            match asset_trait.totals_fit(self.size) {
+                Ok(true) => (),
+                Ok(false) => return fail(None, Error::OptionDistributionFailed),
+                Err(error) => return fail(None, error),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TraitDefinition {
+    /// the trait as built by `add_trait`, `add_option` and `set_total` - or the option it could not be built at
+    pub fn to_asset_trait(&self, env: &Env) -> Result<AssetTrait, (Option<String>, Error)> {
+        let name = to_symbol(&self.name).map_err(|error| (None, error))?;
+        let mut asset_trait = AssetTrait { name, desc: Bytes::from_slice(env, self.desc.as_bytes()), options: soroban_sdk::Vec::new(env) };
+        for option in &self.options {
+            let fail = |error| (Some(option.name.clone()), error);
+            let option_name = to_symbol(&option.name).map_err(fail)?;
+            if asset_trait.option_index(option_name.clone()).map_err(fail)?.is_some() {
+                return Err(fail(Error::OptionAlreadyExistsOnTrait));
+            }
+            let value = option.value.to_value(env)
+                .filter(|v| v.is_valid())
+                .ok_or_else(|| fail(Error::InvalidOptionValue))?;
+            let total = match option.quantity {
+                Some(0) => return Err(fail(Error::OptionDistributionFailed)),
+                quantity => quantity.unwrap_or_default(),
+            };
+            asset_trait.options.push_back(TraitOptionItem::new(option_name, Some(value)).with_total(total));
+        }
+        Ok(asset_trait)
+    }
+}
+
+impl ValueDefinition {
+    /// the value to be passed to `add_option` - [`None`] if a color or hash is malformed
+    pub fn to_value(&self, env: &Env) -> Option<TraitOptionValue> {
+        Some(match self {
+            ValueDefinition::None => TraitOptionValue::None,
+            ValueDefinition::Numeric(n) => TraitOptionValue::Numeric(*n),
+            ValueDefinition::Characters(s) => TraitOptionValue::Characters(Bytes::from_slice(env, s.as_bytes())),
+            ValueDefinition::Signed(n) => TraitOptionValue::Signed(*n),
+            ValueDefinition::Boolean(b) => TraitOptionValue::Boolean(*b),
+            ValueDefinition::Decimal { value, decimals } => TraitOptionValue::Decimal(*value, *decimals),
+            ValueDefinition::Color(rgb) => {
+                let hex = rgb.strip_prefix('#')?;
+                if hex.len() != 6 {
+                    return None;
+                }
+                TraitOptionValue::Color(u32::from_str_radix(hex, 16).ok()?)
+            }
+            ValueDefinition::ContentHash(hash) => {
+                let mut bytes = [0u8; 32];
+                if hash.len() != 64 {
+                    return None;
+                }
+                for (i, byte) in bytes.iter_mut().enumerate() {
+                    *byte = u8::from_str_radix(hash.get(i * 2..i * 2 + 2)?, 16).ok()?;
+                }
+                TraitOptionValue::ContentHash(BytesN::from_array(env, &bytes))
+            }
+            ValueDefinition::Range { min, max } => TraitOptionValue::Range(*min, *max),
+        })
+    }
+}
+
+/// the symbol of a trait or option name
+///
+/// fails with [`Error::MissingName`] if the name is empty and with [`Error::InvalidName`] if it is not a valid symbol
+/// (at most 10 characters of `a-z`, `A-Z`, `0-9` and `_`)
+pub fn to_symbol(name: &str) -> Result<Symbol, Error> {
+    if name.is_empty() {
+        return Err(Error::MissingName);
+    }
+    if name.len() > 10 || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(Error::InvalidName);
+    }
+    Ok(Symbol::from_str(name))
+}
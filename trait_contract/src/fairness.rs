@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use crate::{get_random_number, AssetTrait, RerollConfig, TraitOptionItem, TraitOptionValue, ASSIGNED, TRAITS};
+    use crate::testutils::{seeded_client, seeded_env};
+    use soroban_sdk::{bytes, symbol, testutils, vec, BytesN, Env, Map, Symbol, Vec};
+
+    /// critical values of the chi-square distribution at p = 0.001 by degrees of freedom (index 0 => 1 degree)
+    const CHI_SQUARE_CRITICAL: [f64; 5] = [10.828, 13.816, 16.266, 18.467, 20.515];
+
+    /// sum of (observed - expected)^2 / expected over all options, expecting them in proportion to their weights
+    fn chi_square(observed: &[u32], weights: &[u32]) -> f64 {
+        let draws: u32 = observed.iter().sum();
+        let weight_sum: u32 = weights.iter().sum();
+        observed.iter().zip(weights).map(|(o, w)| {
+            let expected = draws as f64 * *w as f64 / weight_sum as f64;
+            (*o as f64 - expected) * (*o as f64 - expected) / expected
+        }).sum()
+    }
+
+    fn asset_trait(env: &Env, totals: &[u32]) -> AssetTrait {
+        let names = [symbol!("option_1"), symbol!("option_2"), symbol!("option_3"), symbol!("option_4"), symbol!("option_5")];
+        let mut options = vec![env];
+        for (i, total) in totals.iter().enumerate() {
+            options.push_back(TraitOptionItem::new(names[i].clone(), Some(TraitOptionValue::Numeric(i as u32))).with_distribution(*total));
+        }
+        AssetTrait{name: symbol!("trait_1"), desc: bytes!(env, 0xff), options}
+    }
+
+    #[test]
+    fn picks_are_proportional_to_totals() {
+        let totals = [10, 30, 60];
+        let mut observed = [0u32; 3];
+        for seed in 0..50 {
+            let env = seeded_env(seed);
+            // plenty of options so none gets exhausted
+            let asset_trait = asset_trait(&env, &totals.map(|t| t * 1_000));
+            for _ in 0..100 {
+                env.budget().reset_default();
+                observed[asset_trait.pick_available_option(&env).unwrap().unwrap() as usize] += 1;
+            }
+        }
+
+        let statistic = chi_square(&observed, &totals);
+        assert!(statistic < CHI_SQUARE_CRITICAL[totals.len() - 2], "chi-square: {}, observed: {:?}", statistic, observed);
+    }
+
+    #[test]
+    fn picks_skip_exhausted_options_proportionally() {
+        let totals = [20, 40, 40];
+        let mut observed = [0u32; 2];
+        for seed in 0..50 {
+            let env = seeded_env(seed);
+            let mut asset_trait = asset_trait(&env, &totals.map(|t| t * 1_000));
+            let exhausted = asset_trait.options.get_unchecked(0).unwrap();
+            asset_trait.options.set(0, TraitOptionItem{available: 0, ..exhausted});
+            for _ in 0..100 {
+                env.budget().reset_default();
+                let picked = asset_trait.pick_available_option(&env).unwrap().unwrap();
+                assert_ne!(picked, 0);
+                observed[picked as usize - 1] += 1;
+            }
+        }
+
+        let statistic = chi_square(&observed, &totals[1..]);
+        assert!(statistic < CHI_SQUARE_CRITICAL[0], "chi-square: {}, observed: {:?}", statistic, observed);
+    }
+
+    #[test]
+    fn drawing_the_whole_collection_assigns_every_option_its_total() {
+        for seed in 0..40 {
+            let (client, admin) = seeded_client(seed);
+            let env = &client.env;
+            let size = 50;
+
+            client.init(&bytes!(&env, 0xff), &size, &RerollConfig::default());
+            client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+            client.add_trait(&symbol!("trait_2"), &bytes!(env, 0xff));
+            for (i, option) in [symbol!("option_1"), symbol!("option_2"), symbol!("option_3")].iter().enumerate() {
+                client.add_option(&symbol!("trait_1"), option, &TraitOptionValue::Numeric(i as u32));
+                client.add_option(&symbol!("trait_2"), option, &TraitOptionValue::Numeric(i as u32));
+            }
+            client.add_option(&symbol!("trait_2"), &symbol!("option_4"), &TraitOptionValue::Numeric(3));
+            client.finalize();
+
+            for _ in 0..size / 10 {
+                env.budget().reset_default();
+                let mut ids: Vec<BytesN<32>> = vec![&env];
+                for _ in 0..10 {
+                    ids.push_back(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>);
+                }
+                client.draw_many(&admin, &ids);
+
+                // no option is ever issued beyond its supply
+                for trait_stats in client.stats().iter().map(|r| r.unwrap()) {
+                    for option in trait_stats.options.iter().map(|r| r.unwrap()) {
+                        assert!(option.issued <= option.total, "seed {}: {:?}", seed, option);
+                    }
+                }
+            }
+
+            let (asset_traits, assigned) = env.as_contract(&client.contract_id, || (
+                env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS).unwrap(),
+                env.storage().get_unchecked::<_, Map<BytesN<32>, Map<Symbol, TraitOptionValue>>>(ASSIGNED).unwrap(),
+            ));
+            assert_eq!(assigned.len(), size);
+            for asset_trait in asset_traits.iter().map(|r| r.unwrap()) {
+                for option in asset_trait.options.iter().map(|r| r.unwrap()) {
+                    let drawn = assigned.values().iter()
+                        .map(|r| r.unwrap())
+                        .filter(|trait_set| trait_set.get_unchecked(asset_trait.name.clone()).unwrap() == option.value)
+                        .count() as u32;
+                    assert_eq!((drawn, option.available), (option.total, 0), "seed {}: {:?}", seed, option);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn distribute_options_adds_up_to_the_collection_size() {
+        for seed in 0..200 {
+            let env = seeded_env(seed);
+            let size = get_random_number(&env, 1, 500);
+            let option_count = get_random_number(&env, 1, size.min(5));
+            let asset_trait = asset_trait(&env, &[0, 0, 0, 0, 0][..option_count as usize]);
+
+            let distributed = asset_trait.distribute_options(size, env.clone()).unwrap();
+            let total: u32 = distributed.options.iter().map(|r| r.unwrap().total).sum();
+            assert_eq!(total, size, "seed {}", seed);
+            assert!(distributed.options.iter().map(|r| r.unwrap()).all(|o| o.total >= 1 && o.available == o.total), "seed {}", seed);
+        }
+    }
+}
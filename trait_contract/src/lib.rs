@@ -1,17 +1,50 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use soroban_sdk::{contractimpl, map, panic_with_error, symbol, vec, Bytes, BytesN, Env, Map, Symbol, Vec};
-use types::*;
+use soroban_auth::{Identifier, Signature};
+use soroban_sdk::{contractimpl, map, symbol, vec, AccountId, Address, Bytes, BytesN, Env, IntoVal, Map, RawVal, Symbol, TryFromVal, Vec};
+use soroban_sdk::serde::Serialize;
+pub use types::*;
 use rand::Rng;
 use soroban_rand::SorobanRng;
 
+#[cfg(feature = "std")]
+pub mod definition;
+mod fairness;
+pub mod merkle;
+mod migrations;
+mod properties;
+mod strkey;
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils;
 mod types;
 mod tests;
 
 pub const TRAITS: Symbol = symbol!("traits");
 pub const ASSIGNED: Symbol = symbol!("assigned");
+pub const PICKS: Symbol = symbol!("picks");
+pub const ISSUED: Symbol = symbol!("issued");
+pub const OWNERS: Symbol = symbol!("owners");
+pub const REROLLS: Symbol = symbol!("rerolls");
+pub const APPROVALS: Symbol = symbol!("approvals");
+pub const PARENTS: Symbol = symbol!("parents");
+pub const BRED: Symbol = symbol!("bred");
+pub const SEASONS: Symbol = symbol!("seasons");
+pub const VERSION: Symbol = symbol!("version");
+pub const PAUSED: Symbol = symbol!("paused");
+pub const SCHEDULE: Symbol = symbol!("schedule");
+pub const DRAWS: Symbol = symbol!("draws");
+pub const PHASE_DRAWS: Symbol = symbol!("ph_draws");
+pub const ASSETS: Symbol = symbol!("assets");
+pub const PROVENANCE: Symbol = symbol!("provenance");
+pub const ADMIN: Symbol = symbol!("admin");
+pub const NODES: Symbol = symbol!("nodes");
+pub const LEAF_CNT: Symbol = symbol!("leaf_count");
+pub const LEAF_IDX: Symbol = symbol!("leaf_index");
+const REROLL_CFG: Symbol = symbol!("reroll_cfg");
+const BREED_CFG: Symbol = symbol!("breed_cfg");
 const COLLECTION: Symbol = symbol!("collection");
 const IS_FINAL: Symbol = symbol!("final");
+const EMPTY_OK: Symbol = symbol!("empty_ok");
 const EMPTY: Symbol = symbol!("");
 
 pub fn get_random_number(e: &Env, min: u32, max: u32) -> u32 {
@@ -19,6 +52,34 @@ pub fn get_random_number(e: &Env, min: u32, max: u32) -> u32 {
     rng.gen_range(min..=max)
 }
 
+/// the value of a trait within a trait-set - or the name of the option picked for it - failing with
+/// [`Error::OptionNotFound`] if the trait-set lacks the trait
+fn trait_value<V: IntoVal<Env, RawVal> + TryFromVal<Env, RawVal>>(trait_set: &Map<Symbol, V>, trait_name: Symbol) -> Result<V, Error> {
+    trait_set.get(trait_name)
+        .ok_or(Error::OptionNotFound)?
+        .or_invalid()
+}
+
+/// reading stored data - failing with [`Error::InvalidStorage`] if it cannot be converted to the expected type
+pub(crate) trait Stored<T> {
+    fn or_invalid(self) -> Result<T, Error>;
+}
+
+impl<T, E> Stored<T> for Result<T, E> {
+    fn or_invalid(self) -> Result<T, Error> {
+        self.map_err(|_| Error::InvalidStorage)
+    }
+}
+
+impl<T, E> Stored<T> for Option<Result<T, E>> {
+    fn or_invalid(self) -> Result<T, Error> {
+        match self {
+            Some(Ok(value)) => Ok(value),
+            _ => Err(Error::InvalidStorage),
+        }
+    }
+}
+
 pub struct TraitContract;
 
 #[contractimpl]
@@ -29,14 +90,20 @@ impl TraitContract {
     /// The name is not evaluated at the moment. Could be used to allow the contract to
     /// manage multiple collections.
     /// The size determines how many trait-sets can be drawn from the contract.
+    /// The [`RerollConfig`] limits how often holders may reroll a trait of their ids and which
+    /// [`Fee`] they have to pay for it.
+    /// The invoker becomes the admin of the contract.
     ///
     /// Returns the created [`TraitCollection`] as confirmation.
-    pub fn init(env: Env, name: Bytes, size: u32) -> Result<TraitCollection, Error>{
+    pub fn init(env: Env, name: Bytes, size: u32, rerolls: RerollConfig) -> Result<TraitCollection, Error> {
         if env.storage().has(COLLECTION) {
-            panic_with_error!(&env, Error::AlreadyInitialized)
+            return Err(Error::AlreadyInitialized);
         }
         let collection: TraitCollection = TraitCollection{name: name, size: size};
         env.storage().set(COLLECTION, &collection);
+        env.storage().set(VERSION, migrations::CURRENT_VERSION);
+        env.storage().set(ADMIN, env.invoker());
+        env.storage().set(REROLL_CFG, rerolls);
         Ok(collection)
     }
 
@@ -49,43 +116,76 @@ impl TraitContract {
     /// Returns all traits that have been added to the contract.
     ///
     /// Returns a [`Vec`] of all currently existing [`AssetTrait`] of the contract.
-    pub fn add_trait(env: Env, name: Symbol, desc: Bytes) -> Vec<AssetTrait> {
-        Self::expect_initialized(env.clone());
-        let mut traits: Vec<AssetTrait> = Self::get_traits(env.clone());
-        if traits.iter().any(|r| r.unwrap_or_default().name == name) {
-            panic_with_error!(&env, Error::TraitExists)
+    pub fn add_trait(env: Env, name: Symbol, desc: Bytes) -> Result<Vec<AssetTrait>, Error> {
+        Self::expect_initialized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        let mut traits: Vec<AssetTrait> = Self::get_traits(env.clone())?;
+        for asset_trait in traits.iter() {
+            if asset_trait.or_invalid()?.name == name {
+                return Err(Error::TraitExists);
+            }
         }
         traits.push_back(AssetTrait{name, desc, options: vec![&env]});
         env.storage().set(TRAITS, &traits);
-        traits
+        Ok(traits)
     }
 
     /// Add an option to a trait.
     ///
     /// Add a [`TraitOptionItem`] to an [`AssetTrait`] identified by name.
+    /// Decimal values must not have more than [`TraitOptionValue::MAX_DECIMALS`] decimal places and colors
+    /// must be within 0xFFFFFF.
     ///
     /// Returns the trait with all currently added options.
     ///
     /// Return [`AssetTrait`]
-    pub fn add_option(env: Env, to_trait: Symbol, option_name: Symbol, option_value: TraitOptionValue) -> AssetTrait {
-        Self::expect_initialized(env.clone());
-        assert!(option_name != EMPTY, "Must provide an option name");
-        if let Some(mut found) = Self::get_trait(env.clone(), to_trait) {
-            if Self::trait_has_option(found.clone(), option_name) {
-                panic_with_error!(&env, Error::OptionAlreadyExistsOnTrait);
+    pub fn add_option(env: Env, to_trait: Symbol, option_name: Symbol, option_value: TraitOptionValue) -> Result<AssetTrait, Error> {
+        Self::expect_initialized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        if option_name == EMPTY {
+            return Err(Error::MissingName);
+        }
+        if !option_value.is_valid() {
+            return Err(Error::InvalidOptionValue);
+        }
+        if let Some(mut found) = Self::get_trait(env.clone(), to_trait)? {
+            if Self::trait_has_option(found.clone(), option_name)? {
+                return Err(Error::OptionAlreadyExistsOnTrait);
             }
             found.options.push_back(TraitOptionItem::new(option_name, Some(option_value)));
-            Self::update_trait(env.clone(), found.clone());
-            found
+            Self::update_trait(env.clone(), found.clone())?;
+            Ok(found)
         } else {
-            panic_with_error!(&env, Error::TraitNotFound);
+            Err(Error::TraitNotFound)
         }
     }
 
+    /// Fix the total of an option.
+    ///
+    /// Instead of being distributed randomly on `finalize` the option gets exactly the given total, while the rest of
+    /// the collection's size is distributed over the other options of the trait. A total of 0 leaves the option to be
+    /// distributed randomly again. The fixed totals must leave at least one for every other option - or add up to the
+    /// collection's size if every option has a fixed total - see `preflight`. Totals are fixed before finalizing.
+    ///
+    /// Returns the trait with all currently added options.
+    pub fn set_total(env: Env, to_trait: Symbol, option_name: Symbol, total: u32) -> Result<AssetTrait, Error> {
+        Self::expect_initialized(env.clone())?;
+        Self::expect_not_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        let mut found = Self::get_trait(env.clone(), to_trait)?
+            .ok_or(Error::TraitNotFound)?;
+        let option_index = found.option_index(option_name)?
+            .ok_or(Error::OptionNotFound)?;
+        let option = found.options.get_unchecked(option_index).or_invalid()?;
+        found.options.set(option_index, option.with_total(total));
+        Self::update_trait(env.clone(), found.clone())?;
+        Ok(found)
+    }
+
     /// Finalize the contract
     ///
     /// Finalizing a contract will prevent adding more traits or options. Furthermore it will distribute the options
-    /// over the size of the collection.
+    /// over the size of the collection - options with a fixed total (see [`TraitContract::set_total`]) keep it.
     ///
     /// Given a [`TraitCollection`].size of 10 and a [`AssetTrait`] with three [`TraitOptionItem`],
     /// the options for the trait will be randomly distributed over the collection.
@@ -117,32 +217,277 @@ impl TraitContract {
     ///   ],
     /// }
     /// ```
-    pub fn finalize(env: Env) -> bool {
-        Self::expect_initialized(env.clone());
-        let collection_size = env.storage()
-            .get_unchecked::<_, TraitCollection>(COLLECTION)
-            .unwrap_or_default()
-            .size;
-        let traits = Self::get_traits(env.clone()).clone().iter();
-        // all traits must have at least one option but not more options than collection size
-        if traits.map(|r|r.unwrap_or_default()).any(|t| !t.check_is_ready(collection_size)) {
-            panic_with_error!(&env, Error::TraitNotReady);
-        }
-
-        let mut asset_traits = Self::get_traits(env.clone());
+    pub fn finalize(env: Env) -> Result<bool, Error> {
+        Self::expect_initialized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        let collection_size = Self::get_collection(env.clone())?.size;
+        if let Some(problem) = Self::preflight(env.clone())?.first() {
+            return Err(problem.or_invalid()?.error());
+        }
+
+        let mut asset_traits = Self::get_traits(env.clone())?;
         for i in 0..asset_traits.len() {
-            if let Some(ut) = asset_traits.get_unchecked(i).unwrap().distribute_options(collection_size, env.clone()) {
-                asset_traits.set(i, ut);
-            } else {
-                panic_with_error!(&env, Error::OptionDistributionFailed);
-            }
+            let distributed = asset_traits.get_unchecked(i).or_invalid()?.distribute_options(collection_size, env.clone())?;
+            asset_traits.set(i, distributed);
         }
         env.storage().set(TRAITS, asset_traits);
 
         env.storage().set(ASSIGNED, map!(&env) as Map<BytesN<32>, Map<Symbol, TraitOptionValue>>);
 
+        Self::commit_provenance(env.clone())?;
+
         env.storage().set(IS_FINAL, true);
-        env.storage().get(IS_FINAL).unwrap_or_else(|| Ok(false)).unwrap_or_default()
+        env.storage().get(IS_FINAL).unwrap_or_else(|| Ok(false)).or_invalid()
+    }
+
+    /// Check whether the collection is ready to be finalized.
+    ///
+    /// Returns every [`Problem`] `finalize` would refuse for - the collection's problems first, followed by the
+    /// problems of each trait in order. An empty list means the collection can be finalized.
+    pub fn preflight(env: Env) -> Result<Vec<Problem>, Error> {
+        Self::expect_initialized(env.clone())?;
+        let collection_size = Self::get_collection(env.clone())?.size;
+        let traits = Self::get_traits(env.clone())?;
+        let mut problems = vec![&env];
+        if collection_size == 0 {
+            problems.push_back(Problem::SizeZero);
+        }
+        if traits.len() == 0 && !env.storage().get::<_, bool>(EMPTY_OK).unwrap_or_else(|| Ok(false)).or_invalid()? {
+            problems.push_back(Problem::NoTraits);
+        }
+        for i in 0..traits.len() {
+            let asset_trait = traits.get_unchecked(i).or_invalid()?;
+            problems.append(&asset_trait.problems(&env, i, collection_size)?);
+        }
+        Ok(problems)
+    }
+
+    /// Allow finalizing the collection without any traits.
+    ///
+    /// Collections without traits can only be finalized after explicitly opting in - each id drawn from them gets an
+    /// empty trait-set. Only the admin of the contract may opt in - before finalizing.
+    pub fn set_empty(env: Env, allowed: bool) -> Result<(), Error> {
+        Self::expect_initialized(env.clone())?;
+        Self::expect_not_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_admin(env.clone())?;
+        env.storage().set(EMPTY_OK, allowed);
+        Ok(())
+    }
+
+    /// The provenance hash committed to when finalizing.
+    ///
+    /// The hash is the sha256 over the serialized [`TraitCollection`] and all of its [`AssetTrait`]s with their distributed
+    /// options - regardless of how many options have been drawn since. It proves that the configuration of the collection
+    /// has not been changed after finalizing. Expanding the collection commits to a new provenance hash.
+    /// Contracts finalized before provenance hashes were committed commit to one when migrated.
+    pub fn provenance(env: Env) -> Result<BytesN<32>, Error> {
+        match env.storage().get(PROVENANCE) {
+            Some(hash) => hash.or_invalid(),
+            None => Err(Error::NotFinalized),
+        }
+    }
+
+    /// Verify the committed provenance hash against the current configuration of the collection.
+    pub fn verify(env: Env) -> Result<bool, Error> {
+        Ok(Self::provenance(env.clone())? == Self::compute_provenance(env)?)
+    }
+
+    /// Expand the finalized collection by a number of trait-sets.
+    ///
+    /// Optionally adds new options to existing traits. The extra size is then distributed over all options of
+    /// each trait the same way as on [`TraitContract::finalize`] - so each trait must not have more options than
+    /// the extra size. Options already assigned and still available are kept.
+    /// The extra size must not be 0 and a collection without traits cannot be expanded.
+    /// Only the admin of the contract may expand the collection.
+    ///
+    /// Every expansion is recorded as a new [`Season`].
+    ///
+    /// Returns the [`Season`] of the expansion.
+    pub fn expand(env: Env, extra_size: u32, new_options: Vec<NewOption>) -> Result<Season, Error> {
+        Self::expect_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_admin(env.clone())?;
+
+        let mut collection = Self::get_collection(env.clone())?;
+        collection.size = collection.size.checked_add(extra_size)
+            .filter(|_| extra_size > 0)
+            .ok_or(Error::InvalidSize)?;
+        let mut asset_traits = Self::get_traits(env.clone())?;
+        if asset_traits.is_empty() {
+            return Err(Error::CollectionNotReady);
+        }
+        for new_option in new_options.iter() {
+            let new_option = new_option.or_invalid()?;
+            if new_option.name == EMPTY {
+                return Err(Error::MissingName);
+            }
+            if !new_option.value.is_valid() {
+                return Err(Error::InvalidOptionValue);
+            }
+            let trait_index = Self::trait_index(&asset_traits, new_option.trait_name)?;
+            let mut current_trait = asset_traits.get_unchecked(trait_index).or_invalid()?;
+            if Self::trait_has_option(current_trait.clone(), new_option.name.clone())? {
+                return Err(Error::OptionAlreadyExistsOnTrait);
+            }
+            current_trait.options.push_back(TraitOptionItem::new(new_option.name, Some(new_option.value)));
+            asset_traits.set(trait_index, current_trait);
+        }
+
+        for i in 0..asset_traits.len() {
+            let current_trait = asset_traits.get_unchecked(i).or_invalid()?;
+            if !current_trait.check_is_ready(extra_size) {
+                return Err(Error::TraitNotReady);
+            }
+            asset_traits.set(i, current_trait.expand_options(extra_size, env.clone())?);
+        }
+        env.storage().set(TRAITS, asset_traits);
+        env.storage().set(COLLECTION, collection);
+
+        let mut seasons = Self::get_seasons(env.clone())?;
+        let season = Season{number: seasons.len() + 1, extra_size, timestamp: env.ledger().timestamp()};
+        seasons.push_back(season.clone());
+        env.storage().set(SEASONS, seasons);
+        Self::commit_provenance(env.clone())?;
+
+        env.events().publish((symbol!("expanded"), season.number), season.clone());
+        Ok(season)
+    }
+
+    /// All [`Season`]s the collection has been expanded by.
+    pub fn seasons(env: Env) -> Result<Vec<Season>, Error> {
+        Self::get_seasons(env)
+    }
+
+    /// The distribution of every trait's options and how many of them have been issued so far.
+    ///
+    /// Options taken by rerolls count as issued while the options given up by them, or by released ids, do not.
+    /// Bred ids never take options from the supply and are not accounted for.
+    pub fn stats(env: Env) -> Result<Vec<TraitStats>, Error> {
+        Self::expect_finalized(env.clone())?;
+        let mut stats = vec![&env];
+        for asset_trait in Self::get_traits(env.clone())?.iter() {
+            stats.push_back(asset_trait.or_invalid()?.stats(&env)?);
+        }
+        Ok(stats)
+    }
+
+    /// The rarity score of an assigned id.
+    ///
+    /// The score is the sum of the inverse frequencies (collection size / option's total) of the id's options,
+    /// in units of 1/[`AssetTrait::RARITY_SCALE`]. E.g. in a collection of 4 an option with a total of 1 scores
+    /// 4_000 while an option with a total of 3 scores 1_333.
+    pub fn rarity(env: Env, id: BytesN<32>) -> Result<u64, Error> {
+        Self::expect_finalized(env.clone())?;
+        let picked = Self::get_picks(env.clone())?.get(id)
+            .ok_or(Error::NotAssigned)?
+            .or_invalid()?;
+        Self::compute_rarity(&Self::get_traits(env.clone())?, Self::get_collection(env.clone())?.size, picked)
+    }
+
+    /// The percentile of an assigned id's [`TraitContract::rarity`] among all assigned ids.
+    ///
+    /// Returns the share of assigned ids scoring at most as high as the given id in basis points (1/10_000) - the
+    /// rarest ids being ranked at 10_000.
+    ///
+    /// This is the `rarity_rank` view - named `rank` as contract function names are limited to 10 characters.
+    pub fn rank(env: Env, id: BytesN<32>) -> Result<u32, Error> {
+        let score = Self::rarity(env.clone(), id)?;
+        let asset_traits = Self::get_traits(env.clone())?;
+        let size = Self::get_collection(env.clone())?.size;
+        let picks = Self::get_picks(env.clone())?;
+        let mut ranked_below = 0u64;
+        for picked in picks.values().iter() {
+            if Self::compute_rarity(&asset_traits, size, picked.or_invalid()?)? <= score {
+                ranked_below += 1;
+            }
+        }
+        Ok((ranked_below * 10_000 / picks.len() as u64) as u32)
+    }
+
+    /// Set the schedule of when drawing is allowed.
+    ///
+    /// Drawing is only allowed from the schedule's start until (excluding) its end - based on the ledger's timestamp.
+    /// If the schedule has phases, drawing is only allowed during one of them and each drawn id is charged with the
+    /// phase's price. A phase with an allowlist only allows the listed addresses to draw.
+    /// Only the admin of the contract may set the schedule.
+    ///
+    /// Returns the schedule as confirmation.
+    pub fn set_sched(env: Env, schedule: MintSchedule) -> Result<MintSchedule, Error> {
+        Self::expect_initialized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_admin(env.clone())?;
+        if !schedule.is_valid()? {
+            return Err(Error::InvalidSchedule);
+        }
+        env.storage().set(SCHEDULE, schedule.clone());
+        Ok(schedule)
+    }
+
+    /// Pause all changes to the contract.
+    ///
+    /// While paused, drawing and every other invocation that changes the contract's data fails with
+    /// [`Error::Paused`] - read-only invocations keep working. Only the admin of the contract may pause.
+    pub fn pause(env: Env) -> Result<(), Error> {
+        Self::expect_initialized(env.clone())?;
+        Self::expect_admin(env.clone())?;
+        env.storage().set(PAUSED, true);
+        env.events().publish((symbol!("paused"),), env.invoker());
+        Ok(())
+    }
+
+    /// Resume changes to a paused contract.
+    ///
+    /// Only the admin of the contract may unpause.
+    pub fn unpause(env: Env) -> Result<(), Error> {
+        Self::expect_initialized(env.clone())?;
+        Self::expect_admin(env.clone())?;
+        env.storage().set(PAUSED, false);
+        env.events().publish((symbol!("unpaused"),), env.invoker());
+        Ok(())
+    }
+
+    /// Whether the contract is paused.
+    pub fn is_paused(env: Env) -> Result<bool, Error> {
+        env.storage()
+            .get(PAUSED)
+            .unwrap_or_else(|| Ok(false))
+            .or_invalid()
+    }
+
+    /// Upgrade the stored data to the layout of the current contract version.
+    ///
+    /// Storage written by an older version of the contract is migrated step by step - one version at a time.
+    /// Until migrated all other invocations will fail with [`Error::MigrationRequired`].
+    /// Only the admin of the contract may migrate. Unversioned contracts had no admin - the account with the key
+    /// the contract has been built with (`MIGRATION_ADMIN`) migrating such a contract becomes its admin. Anyone else
+    /// fails with [`Error::NotAuthorized`].
+    ///
+    /// Returns the version the storage has been migrated to.
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        if !env.storage().has(COLLECTION) {
+            return Err(Error::NotInitialized);
+        }
+        let mut version = Self::version(env.clone())?;
+        if version == migrations::UNVERSIONED && !env.storage().has(ADMIN) {
+            Self::claim_admin(env.clone())?;
+        }
+        Self::expect_admin(env.clone())?;
+
+        while version < migrations::CURRENT_VERSION {
+            version = migrations::migrate_from(&env, version)?;
+            env.storage().set(VERSION, version);
+            env.events().publish((symbol!("migrated"),), version);
+        }
+        Ok(version)
+    }
+
+    /// The version of the stored data's layout.
+    pub fn version(env: Env) -> Result<u32, Error> {
+        env.storage()
+            .get(VERSION)
+            .unwrap_or_else(|| Ok(migrations::UNVERSIONED))
+            .or_invalid()
     }
 
     /// Draw an option (trait-set) from the pool.
@@ -154,79 +499,875 @@ impl TraitContract {
     /// - subsequent invokes with the same id will return the already assigned option => there is only one option per ID
     /// - after <collection size>-amount of results have been drawn the collection is exhausted and subsequent invokes (except for
     ///    already known IDs, see above) will fail.
-    pub fn draw(env: Env, id: BytesN<32>) -> Result<Map<Symbol, TraitOptionValue>, Error> {
-        Self::expect_finalized(env.clone());
+    /// - the caller must be the invoker and becomes the holder of the id. Each caller may only draw up to the maximum
+    ///    of the [`MintSchedule`] overall and up to the maximum of a [`Phase`] during the phase.
+    pub fn draw(env: Env, caller: Address, id: BytesN<32>) -> Result<Map<Symbol, TraitOptionValue>, Error> {
+        Self::expect_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_invoker(env.clone(), caller.clone())?;
 
-        let mut assigned_traits = env.storage()
-            .get_unchecked::<_, Map<BytesN<32>, Map<Symbol, TraitOptionValue>>>(ASSIGNED).unwrap();
+        let mut assigned_traits = Self::get_assigned(env.clone())?;
+        if let Some(selected_options) = assigned_traits.get(id.clone()) {
+            return selected_options.or_invalid();
+        }
 
-        if assigned_traits.len() == env.storage().get_unchecked::<_, TraitCollection>(COLLECTION)
-            .unwrap().size {
-                panic_with_error!(&env, Error::NoTraitsLeft)
+        let (schedule, phase) = Self::expect_mint_active(env.clone(), caller.clone())?;
+        let issued = Self::get_issued(env.clone())?;
+        if issued >= Self::get_collection(env.clone())?.size {
+            return Err(Error::NoTraitsLeft);
         }
+        Self::count_draws(env.clone(), caller.clone(), schedule, phase.clone(), 1)?;
 
-        let mut selected_options = assigned_traits.get(id.clone())
-            .unwrap_or_else(||Ok(map![&env])).unwrap();
+        let mut asset_traits = Self::get_traits(env.clone())?;
+        let mut picks = Self::get_picks(env.clone())?;
+        let mut owners = Self::get_owners(env.clone())?;
+        let (selected_options, picked) = Self::draw_options(env.clone(), &mut asset_traits)?;
+        env.storage().set(TRAITS, asset_traits);
+        picks.set(id.clone(), picked);
+        env.storage().set(PICKS, picks);
+        owners.set(id.clone(), caller);
+        env.storage().set(OWNERS, owners);
+        assigned_traits.set(id.clone(), selected_options.clone());
+        env.storage().set(ASSIGNED, assigned_traits);
+        env.storage().set(ISSUED, issued + 1);
+        Self::commit_assignments(env.clone(), map![&env, (id, selected_options.clone())])?;
+        if let Some((_, phase)) = phase {
+            Self::charge(env.clone(), phase.price, 1)?;
+        }
 
-        if selected_options.len() > 0 {
-            return Ok(selected_options);
+        // todo: figure a way to identify trait-set
+        // for now just use input
+        Ok(selected_options)
+    }
+
+    /// Draw an option (trait-set) for an asset.
+    ///
+    /// Like [`TraitContract::draw`] but the id is derived from the asset's code and issuer by the contract (see
+    /// [`TraitContract::asset_id`]). The asset is stored along with the id to be looked up by [`TraitContract::asset`].
+    ///
+    /// This is the `draw_for_asset` entry point - named `draw_asset` as contract function names are limited to
+    /// 10 characters.
+    pub fn draw_asset(env: Env, caller: Address, code: Bytes, issuer: Address) -> Result<Map<Symbol, TraitOptionValue>, Error> {
+        let id = Self::asset_id(env.clone(), code.clone(), issuer.clone())?;
+        let selected_options = Self::draw(env.clone(), caller, id.clone())?;
+
+        let mut assets = Self::get_assets(env.clone())?;
+        assets.set(id, AssetDescriptor{code, issuer});
+        env.storage().set(ASSETS, assets);
+        Ok(selected_options)
+    }
+
+    /// The id of an asset.
+    ///
+    /// The id is the sha256 of the asset descriptor "CODE:ISSUER" with the issuer's account-id in its strkey
+    /// representation (e.g. "RUSTLING:GCJL24NRWVOEC6H3FTBGY4HWOBVISRMPKSE76TBUUMD7542Y7VTYQO3F").
+    /// The code must consist of 1 to 12 alphanumeric characters and the issuer must be an account.
+    pub fn asset_id(env: Env, code: Bytes, issuer: Address) -> Result<BytesN<32>, Error> {
+        if code.len() == 0 || code.len() > 12 || !code.iter().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::InvalidAsset);
         }
+        let account_id = match issuer {
+            Address::Account(account_id) => account_id,
+            Address::Contract(_) => return Err(Error::InvalidAsset),
+        };
+        let key = Self::account_key(&env, &account_id);
 
-        let asset_traits = Self::get_traits(env.clone());
-        for i in 0..asset_traits.len() {
-            if let Some(Ok(current_trait)) = asset_traits.get(i) {
-                let option_indexes = current_trait.clone().get_available_options(env.clone());
-                let selected_index = option_indexes.get_unchecked(get_random_number(&env, 0, option_indexes.len()-1)).unwrap();
-                if let Some(Ok(option)) = current_trait.options.get(selected_index) {
-                    selected_options.set(current_trait.name, option.value);
+        let mut descriptor = code;
+        descriptor.append(&Bytes::from_slice(&env, b":"));
+        descriptor.append(&strkey::encode_account_id(&env, &key));
+        Ok(env.crypto().sha256(&descriptor))
+    }
+
+    /// The asset an id has been drawn for by [`TraitContract::draw_asset`].
+    pub fn asset(env: Env, id: BytesN<32>) -> Result<AssetDescriptor, Error> {
+        match Self::get_assets(env.clone())?.get(id) {
+            Some(asset) => asset.or_invalid(),
+            None => Err(Error::NotAssigned),
+        }
+    }
+
+    /// The root of the merkle tree over all assignments.
+    ///
+    /// Every id drawn or bred gets a leaf of sha256(0x00 ++ id ++ serialized trait-set), which is replaced whenever its
+    /// trait-set changes by rerolling or swapping and emptied when the id is released. Inner nodes are
+    /// sha256(0x01 ++ left ++ right) and empty subtrees hash to zero.
+    pub fn root(env: Env) -> Result<BytesN<32>, Error> {
+        merkle::root(&env)
+    }
+
+    /// The proof of an id's current trait-set against [`TraitContract::root`].
+    ///
+    /// Hashing the leaf with the siblings from the bottom up - the leaf being the right-hand node on each
+    /// level where the bit of the index is set - results in the root.
+    ///
+    /// This is the `assignment_proof` entry point - named `proof` as contract function names are limited to
+    /// 10 characters.
+    pub fn proof(env: Env, id: BytesN<32>) -> Result<AssignmentProof, Error> {
+        if !Self::get_assigned(env.clone())?.contains_key(id.clone()) {
+            return Err(Error::NotAssigned);
+        }
+        let index = env.storage().get::<_, u32>((LEAF_IDX, id))
+            .ok_or(Error::NotAssigned)?
+            .or_invalid()?;
+        Ok(AssignmentProof {
+            leaf: merkle::leaf(&env, index)?,
+            index,
+            siblings: merkle::siblings(&env, index)?,
+            root: Self::root(env.clone())?,
+        })
+    }
+
+    /// Draw trait-sets for multiple identifiers at once.
+    ///
+    /// Behaves like [`TraitContract::draw`] for each of the given ids but reads and writes the storage
+    /// only once. Already known ids are returned with their current assignment.
+    ///
+    /// Either all ids get a trait-set assigned or - when the collection gets exhausted while
+    /// drawing or when the caller exceeds its maximum of draws - the whole invocation fails and nothing is assigned.
+    ///
+    /// Returns a [`Map`] of every given id to its trait-set.
+    pub fn draw_many(env: Env, caller: Address, ids: Vec<BytesN<32>>) -> Result<Map<BytesN<32>, Map<Symbol, TraitOptionValue>>, Error> {
+        Self::expect_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_invoker(env.clone(), caller.clone())?;
+
+        let (schedule, phase) = Self::expect_mint_active(env.clone(), caller.clone())?;
+        let collection_size = Self::get_collection(env.clone())?.size;
+        let initially_issued = Self::get_issued(env.clone())?;
+        let mut issued = initially_issued;
+        let mut assigned_traits = Self::get_assigned(env.clone())?;
+        let mut picks = Self::get_picks(env.clone())?;
+        let mut owners = Self::get_owners(env.clone())?;
+        let mut asset_traits = Self::get_traits(env.clone())?;
+        let mut drawn: Map<BytesN<32>, Map<Symbol, TraitOptionValue>> = map![&env];
+        let mut newly_drawn: Map<BytesN<32>, Map<Symbol, TraitOptionValue>> = map![&env];
+
+        for id in ids.iter() {
+            let id = id.or_invalid()?;
+            if let Some(selected_options) = assigned_traits.get(id.clone()) {
+                drawn.set(id, selected_options.or_invalid()?);
+                continue;
+            }
+            if issued >= collection_size {
+                return Err(Error::NoTraitsLeft);
+            }
+            let (selected_options, picked) = Self::draw_options(env.clone(), &mut asset_traits)?;
+            picks.set(id.clone(), picked);
+            owners.set(id.clone(), caller.clone());
+            assigned_traits.set(id.clone(), selected_options.clone());
+            newly_drawn.set(id.clone(), selected_options.clone());
+            drawn.set(id, selected_options);
+            issued += 1;
+        }
+
+        Self::count_draws(env.clone(), caller, schedule, phase.clone(), issued - initially_issued)?;
+        env.storage().set(TRAITS, asset_traits);
+        env.storage().set(PICKS, picks);
+        env.storage().set(OWNERS, owners);
+        env.storage().set(ASSIGNED, assigned_traits);
+        env.storage().set(ISSUED, issued);
+        Self::commit_assignments(env.clone(), newly_drawn)?;
+        if let Some((_, phase)) = phase {
+            Self::charge(env.clone(), phase.price, issued - initially_issued)?;
+        }
+        Ok(drawn)
+    }
+
+    /// Release the trait-set of an id back to the pool.
+    ///
+    /// Meant to be invoked when the asset of an id gets burned. Every option the id held becomes
+    /// available again and the id does not count against the collection size anymore.
+    /// Bred ids (see [`TraitContract::breed`]) never took options from the supply so releasing them
+    /// does not return any options and they keep counting against the breeding cap.
+    /// Only the admin of the contract may release ids.
+    ///
+    /// Returns the released trait-set.
+    pub fn release(env: Env, id: BytesN<32>) -> Result<Map<Symbol, TraitOptionValue>, Error> {
+        Self::expect_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_admin(env.clone())?;
+
+        let mut assigned_traits = Self::get_assigned(env.clone())?;
+        let released = assigned_traits.get(id.clone())
+            .ok_or(Error::NotAssigned)?
+            .or_invalid()?;
+        let mut picks = Self::get_picks(env.clone())?;
+        let picked = picks.get(id.clone())
+            .ok_or(Error::NotAssigned)?
+            .or_invalid()?;
+
+        let mut parents = Self::get_parents(env.clone())?;
+        if parents.contains_key(id.clone()) {
+            parents.remove(id.clone());
+            env.storage().set(PARENTS, parents);
+        } else {
+            let mut asset_traits = Self::get_traits(env.clone())?;
+            for i in 0..asset_traits.len() {
+                let mut current_trait = asset_traits.get_unchecked(i).or_invalid()?;
+                if let Some(option_name) = picked.get(current_trait.name.clone()) {
+                    let option_index = current_trait.option_index(option_name.or_invalid()?)?
+                        .ok_or(Error::OptionNotFound)?;
+                    let returned = current_trait.options.get_unchecked(option_index).or_invalid()?
+                        .with_incremented_available()?;
+                    current_trait.options.set(option_index, returned);
+                    asset_traits.set(i, current_trait);
                 }
             }
+            env.storage().set(TRAITS, asset_traits);
+            env.storage().set(ISSUED, Self::get_issued(env.clone())? - 1);
         }
-        assigned_traits.set(id, selected_options.clone());
+
+        picks.remove(id.clone());
+        env.storage().set(PICKS, picks);
+        let mut owners = Self::get_owners(env.clone())?;
+        owners.remove(id.clone());
+        env.storage().set(OWNERS, owners);
+        let mut rerolls = Self::get_rerolls(env.clone())?;
+        rerolls.remove(id.clone());
+        env.storage().set(REROLLS, rerolls);
+        let mut approvals = Self::get_approvals(env.clone())?;
+        approvals.remove(id.clone());
+        env.storage().set(APPROVALS, approvals);
+        let mut assets = Self::get_assets(env.clone())?;
+        assets.remove(id.clone());
+        env.storage().set(ASSETS, assets);
+        assigned_traits.remove(id.clone());
         env.storage().set(ASSIGNED, assigned_traits);
+        Self::clear_assignment(env.clone(), id.clone())?;
 
-        // todo: figure a way to identify trait-set
-        // for now just use input
+        env.events().publish((symbol!("released"), id), released.clone());
+        Ok(released)
+    }
+
+    /// Reroll a single trait of an id.
+    ///
+    /// The currently assigned option of the trait is returned to the pool and a different option
+    /// is drawn from the remaining supply. Only the holder (the invoker that drew the id) may reroll
+    /// and has to pay the [`Fee`] configured on [`TraitContract::init`]. Each id can only be rerolled
+    /// up to the configured limit.
+    /// Bred ids can not be rerolled as their options were not taken from the supply.
+    ///
+    /// Returns the updated trait-set of the id.
+    pub fn reroll(env: Env, id: BytesN<32>, trait_name: Symbol) -> Result<Map<Symbol, TraitOptionValue>, Error> {
+        Self::expect_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_owner(env.clone(), id.clone())?;
+        Self::expect_not_bred(env.clone(), id.clone())?;
+
+        let config = Self::get_reroll_config(env.clone())?;
+        let mut rerolls = Self::get_rerolls(env.clone())?;
+        let rerolled = rerolls.get(id.clone()).unwrap_or_else(|| Ok(0)).or_invalid()?;
+        if rerolled >= config.limit {
+            return Err(Error::RerollLimitReached);
+        }
+
+        let mut asset_traits = Self::get_traits(env.clone())?;
+        let trait_index = Self::trait_index(&asset_traits, trait_name.clone())?;
+        let mut current_trait = asset_traits.get_unchecked(trait_index).or_invalid()?;
+
+        let mut picks = Self::get_picks(env.clone())?;
+        let mut picked = picks.get(id.clone()).or_invalid()?;
+        let current_index = current_trait.option_index(trait_value(&picked, trait_name.clone())?)?
+            .ok_or(Error::OptionNotFound)?;
+        let new_index = current_trait.pick_available_option_except(&env, Some(current_index))?
+            .ok_or(Error::NoAlternativeOption)?;
+
+        Self::charge(env.clone(), config.fee, 1)?;
+
+        let returned = current_trait.options.get_unchecked(current_index).or_invalid()?
+            .with_incremented_available()?;
+        current_trait.options.set(current_index, returned);
+        let new_option = current_trait.options.get_unchecked(new_index).or_invalid()?;
+        current_trait.options.set(new_index, new_option.clone().with_decremented_available()?);
+        asset_traits.set(trait_index, current_trait);
+        env.storage().set(TRAITS, asset_traits);
+
+        picked.set(trait_name.clone(), new_option.name);
+        picks.set(id.clone(), picked);
+        env.storage().set(PICKS, picks);
+
+        let new_value = new_option.value.resolve(&env);
+        let mut assigned_traits = Self::get_assigned(env.clone())?;
+        let mut selected_options = assigned_traits.get(id.clone()).or_invalid()?;
+        selected_options.set(trait_name.clone(), new_value.clone());
+        assigned_traits.set(id.clone(), selected_options.clone());
+        env.storage().set(ASSIGNED, assigned_traits);
+        Self::commit_assignments(env.clone(), map![&env, (id.clone(), selected_options.clone())])?;
+
+        rerolls.set(id.clone(), rerolled + 1);
+        env.storage().set(REROLLS, rerolls);
+
+        env.events().publish((symbol!("rerolled"), id, trait_name), new_value);
         Ok(selected_options)
     }
 
-    fn expect_initialized(env: Env) {
+    /// Approve swapping a trait of an owned id with another id.
+    ///
+    /// The holder of `id` allows the holder of `with` to swap the option of the given trait between both
+    /// ids by invoking [`TraitContract::swap_trait`]. An approval is used up by the swap and replaces any
+    /// former approval of the id.
+    pub fn approve(env: Env, id: BytesN<32>, with: BytesN<32>, trait_name: Symbol) -> Result<(), Error> {
+        Self::expect_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_owner(env.clone(), id.clone())?;
+
+        let mut approvals = Self::get_approvals(env.clone())?;
+        approvals.set(id, SwapApproval{with, trait_name});
+        env.storage().set(APPROVALS, approvals);
+        Ok(())
+    }
+
+    /// Swap the option of a trait between two ids.
+    ///
+    /// Must be invoked by the holder of `id_a`. The holder of `id_b` must either be the same or have approved
+    /// the swap via [`TraitContract::approve`] beforehand. The overall supply of options is not affected.
+    /// Bred ids can not be swapped with as their options were not taken from the supply.
+    ///
+    /// Returns the updated trait-set of `id_a`.
+    pub fn swap_trait(env: Env, id_a: BytesN<32>, id_b: BytesN<32>, trait_name: Symbol) -> Result<Map<Symbol, TraitOptionValue>, Error> {
+        Self::expect_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_owner(env.clone(), id_a.clone())?;
+        Self::expect_not_bred(env.clone(), id_a.clone())?;
+        Self::expect_not_bred(env.clone(), id_b.clone())?;
+
+        let mut approvals = Self::get_approvals(env.clone())?;
+        let approved = match approvals.get(id_b.clone()) {
+            Some(approval) => {
+                let approval = approval.or_invalid()?;
+                approval.with == id_a && approval.trait_name == trait_name
+            }
+            None => false,
+        };
+        let owner_b = Self::get_owners(env.clone())?.get(id_b.clone())
+            .ok_or(Error::NotAssigned)?
+            .or_invalid()?;
+        if !approved && owner_b != env.invoker() {
+            return Err(Error::SwapNotApproved);
+        }
+        Self::trait_index(&Self::get_traits(env.clone())?, trait_name.clone())?;
+
+        let mut assigned_traits = Self::get_assigned(env.clone())?;
+        let mut options_a = assigned_traits.get(id_a.clone()).or_invalid()?;
+        let mut options_b = assigned_traits.get(id_b.clone()).or_invalid()?;
+        let value_a = trait_value(&options_a, trait_name.clone())?;
+        let value_b = trait_value(&options_b, trait_name.clone())?;
+        options_a.set(trait_name.clone(), value_b.clone());
+        options_b.set(trait_name.clone(), value_a.clone());
+        assigned_traits.set(id_a.clone(), options_a.clone());
+        assigned_traits.set(id_b.clone(), options_b.clone());
+        env.storage().set(ASSIGNED, assigned_traits);
+        Self::commit_assignments(env.clone(), map![&env, (id_a.clone(), options_a.clone()), (id_b.clone(), options_b)])?;
+
+        let mut picks = Self::get_picks(env.clone())?;
+        let mut picked_a = picks.get(id_a.clone()).or_invalid()?;
+        let mut picked_b = picks.get(id_b.clone()).or_invalid()?;
+        let option_a = trait_value(&picked_a, trait_name.clone())?;
+        let option_b = trait_value(&picked_b, trait_name.clone())?;
+        picked_a.set(trait_name.clone(), option_b);
+        picked_b.set(trait_name.clone(), option_a);
+        picks.set(id_a.clone(), picked_a);
+        picks.set(id_b.clone(), picked_b);
+        env.storage().set(PICKS, picks);
+
+        if approved {
+            approvals.remove(id_b.clone());
+            env.storage().set(APPROVALS, approvals);
+        }
+
+        env.events().publish((symbol!("swapped"), id_a.clone(), trait_name.clone()), value_b);
+        env.events().publish((symbol!("swapped"), id_b, trait_name), value_a);
+        Ok(options_a)
+    }
+
+    /// Configure breeding of new ids from already assigned ones.
+    ///
+    /// See [`BreedConfig`]. Only the admin of the contract may configure breeding.
+    pub fn set_breed(env: Env, config: BreedConfig) -> Result<BreedConfig, Error> {
+        Self::expect_initialized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        Self::expect_admin(env.clone())?;
+        if config.mutation_bps > 10_000 {
+            return Err(Error::InvalidBreedConfig);
+        }
+        env.storage().set(BREED_CFG, config.clone());
+        Ok(config)
+    }
+
+    /// Breed a child trait-set from two parent ids.
+    ///
+    /// For each trait the child inherits the option of one of the parents by chance. With the configured
+    /// mutation rate the option mutates into a random option of the trait instead - weighted by the
+    /// option's total but regardless of the remaining supply.
+    ///
+    /// Children are assigned like drawn ids but do not take options from the supply and do not count against
+    /// the collection size. Instead the amount of children is capped by [`BreedConfig`].cap.
+    /// The invoker must hold both parents, which must be different ids, and becomes the holder of the child.
+    ///
+    /// Returns the trait-set of the child.
+    pub fn breed(env: Env, parent_a: BytesN<32>, parent_b: BytesN<32>, child_id: BytesN<32>) -> Result<Map<Symbol, TraitOptionValue>, Error> {
+        Self::expect_finalized(env.clone())?;
+        Self::expect_not_paused(env.clone())?;
+        if parent_a == parent_b {
+            return Err(Error::SameParents);
+        }
+        Self::expect_owner(env.clone(), parent_a.clone())?;
+        Self::expect_owner(env.clone(), parent_b.clone())?;
+
+        let config = Self::get_breed_config(env.clone())?;
+        let mut parents = Self::get_parents(env.clone())?;
+        let bred = Self::get_bred(env.clone())?;
+        if bred >= config.cap {
+            return Err(Error::BreedingCapReached);
+        }
+        let mut assigned_traits = Self::get_assigned(env.clone())?;
+        if assigned_traits.contains_key(child_id.clone()) {
+            return Err(Error::AlreadyAssigned);
+        }
+
+        let mut picks = Self::get_picks(env.clone())?;
+        let picked_a = picks.get(parent_a.clone()).or_invalid()?;
+        let picked_b = picks.get(parent_b.clone()).or_invalid()?;
+        let options_a = assigned_traits.get(parent_a.clone()).or_invalid()?;
+        let options_b = assigned_traits.get(parent_b.clone()).or_invalid()?;
+        let asset_traits = Self::get_traits(env.clone())?;
+        let mut selected_options = map![&env];
+        let mut picked = map![&env];
+        for current_trait in asset_traits.iter() {
+            let current_trait = current_trait.or_invalid()?;
+            let name = current_trait.name.clone();
+            let (option_name, value) = if get_random_number(&env, 1, 10_000) <= config.mutation_bps {
+                let index = current_trait.pick_option(&env)?
+                    .ok_or(Error::OptionExhausted)?;
+                let option = current_trait.options.get_unchecked(index).or_invalid()?;
+                (option.name, option.value.resolve(&env))
+            } else if get_random_number(&env, 0, 1) == 0 {
+                (trait_value(&picked_a, name.clone())?, trait_value(&options_a, name.clone())?)
+            } else {
+                (trait_value(&picked_b, name.clone())?, trait_value(&options_b, name.clone())?)
+            };
+            selected_options.set(name.clone(), value);
+            picked.set(name, option_name);
+        }
+
+        assigned_traits.set(child_id.clone(), selected_options.clone());
+        env.storage().set(ASSIGNED, assigned_traits);
+        Self::commit_assignments(env.clone(), map![&env, (child_id.clone(), selected_options.clone())])?;
+        picks.set(child_id.clone(), picked);
+        env.storage().set(PICKS, picks);
+        let mut owners = Self::get_owners(env.clone())?;
+        owners.set(child_id.clone(), env.invoker());
+        env.storage().set(OWNERS, owners);
+        parents.set(child_id.clone(), vec![&env, parent_a, parent_b]);
+        env.storage().set(PARENTS, parents);
+        env.storage().set(BRED, bred + 1);
+
+        env.events().publish((symbol!("bred"), child_id), selected_options.clone());
+        Ok(selected_options)
+    }
+
+    /// sets the leaf of each id to its trait-set - ids not committed before get the next free leaf
+    fn commit_assignments(env: Env, trait_sets: Map<BytesN<32>, Map<Symbol, TraitOptionValue>>) -> Result<(), Error> {
+        let mut count: u32 = env.storage()
+            .get(LEAF_CNT)
+            .unwrap_or_else(|| Ok(0))
+            .or_invalid()?;
+        for entry in trait_sets.iter() {
+            let (id, trait_set) = entry.or_invalid()?;
+            let index = match env.storage().get::<_, u32>((LEAF_IDX, id.clone())) {
+                Some(index) => index.or_invalid()?,
+                None => {
+                    if count as u64 >= 1 << merkle::DEPTH {
+                        return Err(Error::MerkleTreeFull);
+                    }
+                    env.storage().set((LEAF_IDX, id.clone()), count);
+                    count += 1;
+                    count - 1
+                }
+            };
+            merkle::set_leaf(&env, index, merkle::hash_leaf(&env, &id, &trait_set))?;
+        }
+        env.storage().set(LEAF_CNT, count);
+        env.events().publish((symbol!("committed"),), merkle::root(&env)?);
+        Ok(())
+    }
+
+    /// empties the leaf of a released id
+    ///
+    /// the id keeps its leaf index - being drawn or bred again its trait-set is committed to the same leaf.
+    fn clear_assignment(env: Env, id: BytesN<32>) -> Result<(), Error> {
+        if let Some(index) = env.storage().get::<_, u32>((LEAF_IDX, id)) {
+            merkle::clear_leaf(&env, index.or_invalid()?)?;
+            env.events().publish((symbol!("committed"),), merkle::root(&env)?);
+        }
+        Ok(())
+    }
+
+    /// sum of the rarities of the picked options
+    fn compute_rarity(asset_traits: &Vec<AssetTrait>, size: u32, picked: Map<Symbol, Symbol>) -> Result<u64, Error> {
+        let mut score = 0;
+        for asset_trait in asset_traits.iter() {
+            let asset_trait = asset_trait.or_invalid()?;
+            let option_name = picked.get(asset_trait.name.clone())
+                .ok_or(Error::OptionNotFound)?
+                .or_invalid()?;
+            score += asset_trait.rarity(option_name, size)?
+                .ok_or(Error::OptionNotFound)?;
+        }
+        Ok(score)
+    }
+
+    fn commit_provenance(env: Env) -> Result<(), Error> {
+        let hash = Self::compute_provenance(env.clone())?;
+        env.storage().set(PROVENANCE, hash.clone());
+        env.events().publish((symbol!("provenance"),), hash);
+        Ok(())
+    }
+
+    /// sha256 over the serialized collection and its traits with all options being available
+    fn compute_provenance(env: Env) -> Result<BytesN<32>, Error> {
+        let mut asset_traits = Self::get_traits(env.clone())?;
+        for i in 0..asset_traits.len() {
+            asset_traits.set(i, asset_traits.get_unchecked(i).or_invalid()?.as_distributed()?);
+        }
+        let mut encoded = Self::get_collection(env.clone())?.serialize(&env);
+        encoded.append(&asset_traits.serialize(&env));
+        Ok(env.crypto().sha256(&encoded))
+    }
+
+    /// picks one available option for each trait and takes it from the given traits' supply
+    ///
+    /// returns the values of the picked options and the names of the picked options, both by trait name
+    fn draw_options(env: Env, asset_traits: &mut Vec<AssetTrait>) -> Result<(Map<Symbol, TraitOptionValue>, Map<Symbol, Symbol>), Error> {
+        let mut selected_options = map![&env];
+        let mut picked = map![&env];
+        for i in 0..asset_traits.len() {
+            let mut current_trait = asset_traits.get_unchecked(i).or_invalid()?;
+            let selected_index = current_trait.pick_available_option(&env)?
+                .ok_or(Error::OptionExhausted)?;
+            let option = current_trait.options.get_unchecked(selected_index).or_invalid()?;
+            selected_options.set(current_trait.name.clone(), option.value.resolve(&env));
+            picked.set(current_trait.name.clone(), option.name.clone());
+            let drawn = option.with_decremented_available()?;
+            current_trait.options.set(selected_index, drawn);
+            asset_traits.set(i, current_trait);
+        }
+        Ok((selected_options, picked))
+    }
+
+    fn expect_initialized(env: Env) -> Result<(), Error> {
         if !env.storage().has(COLLECTION) {
-            panic_with_error!(&env, Error::NotInitialized)
+            return Err(Error::NotInitialized);
         }
+        Self::expect_current_version(env)
     }
 
-    fn expect_finalized(env: Env) {
-        if !env.storage().get::<_, bool>(IS_FINAL).unwrap_or_else(||Ok(false)).unwrap_or_else(|_|false) {
-            panic_with_error!(&env, Error::NotFinalized)
+    fn expect_not_paused(env: Env) -> Result<(), Error> {
+        if Self::is_paused(env)? {
+            return Err(Error::Paused);
         }
+        Ok(())
     }
 
-    fn get_trait(env: Env, name: Symbol) -> Option<AssetTrait> {
-        assert!(name != EMPTY, "Must provide a trait name");
-        let mut traits = Self::get_traits(env.clone())
-            .into_iter()
-            .map(|r|r.unwrap_or_default());
-        if let Some(found) = traits.find(|t| t.name == name) {
-            Some(found)
-        } else {
-            None
+    fn expect_current_version(env: Env) -> Result<(), Error> {
+        match Self::version(env)? {
+            migrations::CURRENT_VERSION => Ok(()),
+            v if v < migrations::CURRENT_VERSION => Err(Error::MigrationRequired),
+            _ => Err(Error::UnknownVersion),
+        }
+    }
+
+    /// makes the invoker the admin of an unversioned contract if it holds the key the contract has been built with
+    fn claim_admin(env: Env) -> Result<(), Error> {
+        match env.invoker() {
+            Address::Account(account_id) if migrations::MIGRATION_ADMIN == Some(Self::account_key(&env, &account_id)) => {
+                env.storage().set(ADMIN, Address::Account(account_id));
+                Ok(())
+            }
+            _ => Err(Error::NotAuthorized),
+        }
+    }
+
+    /// the ed25519 key of an account - making up the last 32 bytes of the serialized account-id
+    fn account_key(env: &Env, account_id: &AccountId) -> [u8; 32] {
+        let serialized = account_id.serialize(env);
+        let mut key = [0u8; 32];
+        for i in 0..32 {
+            key[i as usize] = serialized.get_unchecked(serialized.len() - 32 + i);
+        }
+        key
+    }
+
+    fn expect_admin(env: Env) -> Result<(), Error> {
+        let admin = env.storage().get::<_, Address>(ADMIN)
+            .ok_or(Error::NotAuthorized)?
+            .or_invalid()?;
+        if admin != env.invoker() {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    fn expect_invoker(env: Env, caller: Address) -> Result<(), Error> {
+        if caller != env.invoker() {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    fn expect_owner(env: Env, id: BytesN<32>) -> Result<(), Error> {
+        let owner = Self::get_owners(env.clone())?
+            .get(id)
+            .ok_or(Error::NotAssigned)?
+            .or_invalid()?;
+        if owner != env.invoker() {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    fn expect_not_bred(env: Env, id: BytesN<32>) -> Result<(), Error> {
+        if Self::get_parents(env.clone())?.contains_key(id) {
+            return Err(Error::BredId);
+        }
+        Ok(())
+    }
+
+    /// checks the mint schedule at the current ledger timestamp
+    ///
+    /// returns the schedule - if any - and the index of its active [`Phase`] along with the phase - if the schedule
+    /// has any phases
+    fn expect_mint_active(env: Env, caller: Address) -> Result<(Option<MintSchedule>, Option<(u32, Phase)>), Error> {
+        let schedule = match env.storage().get::<_, MintSchedule>(SCHEDULE) {
+            Some(schedule) => schedule.or_invalid()?,
+            None => return Ok((None, None)),
+        };
+        let now = env.ledger().timestamp();
+        if now < schedule.start || now >= schedule.end {
+            return Err(Error::MintNotActive);
         }
+        if schedule.phases.len() == 0 {
+            return Ok((Some(schedule), None));
+        }
+        let mut active = None;
+        for i in 0..schedule.phases.len() {
+            let phase = schedule.phases.get_unchecked(i).or_invalid()?;
+            if phase.is_active(now) {
+                active = Some((i, phase));
+                break;
+            }
+        }
+        let (index, phase) = active.ok_or(Error::MintNotActive)?;
+        if phase.allowlist.len() > 0 && !phase.allowlist.contains(caller) {
+            return Err(Error::NotAllowlisted);
+        }
+        Ok((Some(schedule), Some((index, phase))))
+    }
+
+    /// adds the amount of drawn ids to the caller's draws - overall and within the active phase
+    ///
+    /// fails if the caller exceeds the maximum of the schedule with all its draws or the maximum of the active phase
+    /// with its draws during the phase.
+    fn count_draws(env: Env, caller: Address, schedule: Option<MintSchedule>, phase: Option<(u32, Phase)>, drawn: u32) -> Result<(), Error> {
+        let mut draws = Self::get_draws(env.clone())?;
+        let count = draws.get(caller.clone()).unwrap_or_else(|| Ok(0)).or_invalid()? + drawn;
+        if let Some(schedule) = schedule {
+            if schedule.max_per_address > 0 && count > schedule.max_per_address {
+                return Err(Error::DrawLimitReached);
+            }
+        }
+        if let Some((index, phase)) = phase {
+            let mut phase_draws = Self::get_phase_draws(env.clone())?;
+            let key = (index, caller.clone());
+            let phase_count = phase_draws.get(key.clone()).unwrap_or_else(|| Ok(0)).or_invalid()? + drawn;
+            if phase.max_per_address > 0 && phase_count > phase.max_per_address {
+                return Err(Error::DrawLimitReached);
+            }
+            phase_draws.set(key, phase_count);
+            env.storage().set(PHASE_DRAWS, phase_draws);
+        }
+        draws.set(caller, count);
+        env.storage().set(DRAWS, draws);
+        Ok(())
+    }
+
+    /// transfers the fee for the given amount of items from the invoker to this contract
+    ///
+    /// the invoker needs to have approved an allowance for this contract on the fee's token beforehand
+    fn charge(env: Env, fee: Fee, items: u32) -> Result<(), Error> {
+        if let Fee::Token(token, price) = fee {
+            let amount = price * items as i128;
+            if amount == 0 {
+                return Ok(());
+            }
+            let from = match env.invoker() {
+                Address::Account(account_id) => Identifier::Account(account_id),
+                Address::Contract(contract_id) => Identifier::Contract(contract_id),
+            };
+            let to = Identifier::Contract(env.current_contract());
+            env.invoke_contract::<()>(
+                &token,
+                &symbol!("xfer_from"),
+                (Signature::Invoker, 0_i128, from, to, amount).into_val(&env),
+            );
+        }
+        Ok(())
+    }
+
+    fn expect_finalized(env: Env) -> Result<(), Error> {
+        if !env.storage().get::<_, bool>(IS_FINAL).unwrap_or_else(|| Ok(false)).or_invalid()? {
+            return Err(Error::NotFinalized);
+        }
+        Self::expect_current_version(env)
+    }
+
+    fn expect_not_finalized(env: Env) -> Result<(), Error> {
+        if env.storage().get::<_, bool>(IS_FINAL).unwrap_or_else(|| Ok(false)).or_invalid()? {
+            return Err(Error::AlreadyFinalized);
+        }
+        Ok(())
+    }
+
+    fn get_trait(env: Env, name: Symbol) -> Result<Option<AssetTrait>, Error> {
+        if name == EMPTY {
+            return Err(Error::MissingName);
+        }
+        for asset_trait in Self::get_traits(env)?.iter() {
+            let asset_trait = asset_trait.or_invalid()?;
+            if asset_trait.name == name {
+                return Ok(Some(asset_trait));
+            }
+        }
+        Ok(None)
+    }
+
+    fn get_collection(env: Env) -> Result<TraitCollection, Error> {
+        env.storage()
+            .get::<_, TraitCollection>(COLLECTION)
+            .ok_or(Error::NotInitialized)?
+            .or_invalid()
+    }
+
+    fn get_assigned(env: Env) -> Result<Map<BytesN<32>, Map<Symbol, TraitOptionValue>>, Error> {
+        env.storage()
+            .get(ASSIGNED)
+            .unwrap_or_else(|| Ok(map![&env]))
+            .or_invalid()
+    }
+
+    fn get_picks(env: Env) -> Result<Map<BytesN<32>, Map<Symbol, Symbol>>, Error> {
+        env.storage()
+            .get(PICKS)
+            .unwrap_or_else(|| Ok(map![&env]))
+            .or_invalid()
+    }
+
+    fn get_owners(env: Env) -> Result<Map<BytesN<32>, Address>, Error> {
+        env.storage()
+            .get(OWNERS)
+            .unwrap_or_else(|| Ok(map![&env]))
+            .or_invalid()
+    }
+
+    fn get_assets(env: Env) -> Result<Map<BytesN<32>, AssetDescriptor>, Error> {
+        env.storage()
+            .get(ASSETS)
+            .unwrap_or_else(|| Ok(map![&env]))
+            .or_invalid()
+    }
+
+    fn get_draws(env: Env) -> Result<Map<Address, u32>, Error> {
+        env.storage()
+            .get(DRAWS)
+            .unwrap_or_else(|| Ok(map![&env]))
+            .or_invalid()
+    }
+
+    fn get_phase_draws(env: Env) -> Result<Map<(u32, Address), u32>, Error> {
+        env.storage()
+            .get(PHASE_DRAWS)
+            .unwrap_or_else(|| Ok(map![&env]))
+            .or_invalid()
     }
 
-    fn get_traits(env: Env) -> Vec<AssetTrait> {
+    fn get_approvals(env: Env) -> Result<Map<BytesN<32>, SwapApproval>, Error> {
+        env.storage()
+            .get(APPROVALS)
+            .unwrap_or_else(|| Ok(map![&env]))
+            .or_invalid()
+    }
+
+    fn get_rerolls(env: Env) -> Result<Map<BytesN<32>, u32>, Error> {
+        env.storage()
+            .get(REROLLS)
+            .unwrap_or_else(|| Ok(map![&env]))
+            .or_invalid()
+    }
+
+    fn get_reroll_config(env: Env) -> Result<RerollConfig, Error> {
+        env.storage()
+            .get(REROLL_CFG)
+            .unwrap_or_else(|| Ok(RerollConfig::default()))
+            .or_invalid()
+    }
+
+    fn get_parents(env: Env) -> Result<Map<BytesN<32>, Vec<BytesN<32>>>, Error> {
+        env.storage()
+            .get(PARENTS)
+            .unwrap_or_else(|| Ok(map![&env]))
+            .or_invalid()
+    }
+
+    fn get_breed_config(env: Env) -> Result<BreedConfig, Error> {
+        env.storage()
+            .get(BREED_CFG)
+            .unwrap_or_else(|| Ok(BreedConfig::default()))
+            .or_invalid()
+    }
+
+    fn get_bred(env: Env) -> Result<u32, Error> {
+        env.storage()
+            .get(BRED)
+            .unwrap_or_else(|| Ok(0))
+            .or_invalid()
+    }
+
+    fn get_seasons(env: Env) -> Result<Vec<Season>, Error> {
+        env.storage()
+            .get(SEASONS)
+            .unwrap_or_else(|| Ok(vec![&env]))
+            .or_invalid()
+    }
+
+    fn get_issued(env: Env) -> Result<u32, Error> {
+        env.storage()
+            .get(ISSUED)
+            .unwrap_or_else(|| Ok(0))
+            .or_invalid()
+    }
+
+    fn get_traits(env: Env) -> Result<Vec<AssetTrait>, Error> {
         env.storage()
             .get(TRAITS)
             .unwrap_or_else(|| Ok(vec![&env]))
-            .unwrap()
+            .or_invalid()
     }
 
-    fn update_trait(env: Env, updated: AssetTrait) -> bool {
+    fn trait_index(traits: &Vec<AssetTrait>, name: Symbol) -> Result<u32, Error> {
+        for i in 0..traits.len() {
+            if traits.get_unchecked(i).or_invalid()?.name == name {
+                return Ok(i);
+            }
+        }
+        Err(Error::TraitNotFound)
+    }
+
+    fn update_trait(env: Env, updated: AssetTrait) -> Result<bool, Error> {
         let mut was_updated = false;
-        let mut update_traits = Self::get_traits(env.clone());
+        let mut update_traits = Self::get_traits(env.clone())?;
 
         for i in 0..update_traits.len() {
-            let t = update_traits.get_unchecked(i).unwrap();
+            let t = update_traits.get_unchecked(i).or_invalid()?;
             if t.name == updated.name {
                 update_traits.set(i, updated);
                 env.storage().set(TRAITS, update_traits);
@@ -234,14 +1375,10 @@ impl TraitContract {
                 break;
             }
         }
-        was_updated
+        Ok(was_updated)
     }
 
-    fn trait_has_option(t: AssetTrait, option_name: Symbol) -> bool {
-        let mut has_option = false;
-        if let Some(option_exists) = t.options.iter().find(|o|o.is_ok() && o.as_ref().unwrap().name == option_name) {
-            has_option = option_exists.is_ok();
-        }
-        has_option
+    fn trait_has_option(t: AssetTrait, option_name: Symbol) -> Result<bool, Error> {
+        Ok(t.option_index(option_name)?.is_some())
     }
 }
@@ -0,0 +1,106 @@
+use soroban_sdk::{vec, Bytes, BytesN, Env, Map, Symbol, Vec};
+use soroban_sdk::serde::Serialize;
+use crate::types::{AssignmentProof, Error, TraitOptionValue};
+use crate::{Stored, NODES};
+
+/// depth of the tree - allowing for 2^20 leaves
+pub const DEPTH: u32 = 20;
+
+/// the current root of the tree
+pub fn root(env: &Env) -> Result<BytesN<32>, Error> {
+    node(env, DEPTH, 0, &zero(env, DEPTH))
+}
+
+/// the leaf at the index - the empty leaf if none has been set
+pub fn leaf(env: &Env, index: u32) -> Result<BytesN<32>, Error> {
+    node(env, 0, index, &zero(env, 0))
+}
+
+/// sets the leaf at the index and updates the nodes on its path up to the root
+///
+/// only the nodes on the path and their siblings are read and written - nodes of empty subtrees are not stored.
+pub fn set_leaf(env: &Env, index: u32, leaf: BytesN<32>) -> Result<(), Error> {
+    let (mut node, mut index, mut zero_node) = (leaf, index, zero(env, 0));
+    for level in 0..DEPTH {
+        store(env, level, index, &node, &zero_node);
+        let sibling = self::node(env, level, index ^ 1, &zero_node)?;
+        node = if index & 1 == 1 { hash_nodes(env, &sibling, &node) } else { hash_nodes(env, &node, &sibling) };
+        zero_node = hash_nodes(env, &zero_node, &zero_node);
+        index /= 2;
+    }
+    store(env, DEPTH, 0, &node, &zero_node);
+    Ok(())
+}
+
+/// resets the leaf at the index to the empty leaf
+pub fn clear_leaf(env: &Env, index: u32) -> Result<(), Error> {
+    set_leaf(env, index, zero(env, 0));
+}
+
+/// leaf of an id's trait-set: sha256(0x00 ++ id ++ serialized trait-set)
+pub fn hash_leaf(env: &Env, id: &BytesN<32>, trait_set: &Map<Symbol, TraitOptionValue>) -> BytesN<32> {
+    let mut data = Bytes::from_array(env, &[0]);
+    data.append(&Bytes::from(id.clone()));
+    data.append(&trait_set.clone().serialize(env));
+    env.crypto().sha256(&data)
+}
+
+/// inner node: sha256(0x01 ++ left ++ right)
+pub fn hash_nodes(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let mut data = Bytes::from_array(env, &[1]);
+    data.append(&Bytes::from(left.clone()));
+    data.append(&Bytes::from(right.clone()));
+    env.crypto().sha256(&data)
+}
+
+/// the siblings of the leaf at the index on each level
+pub fn siblings(env: &Env, index: u32) -> Result<Vec<BytesN<32>>, Error> {
+    let mut siblings = vec![env];
+    let (mut index, mut zero_node) = (index, zero(env, 0));
+    for level in 0..DEPTH {
+        siblings.push_back(node(env, level, index ^ 1, &zero_node)?);
+        zero_node = hash_nodes(env, &zero_node, &zero_node);
+        index /= 2;
+    }
+    Ok(siblings)
+}
+
+/// verifies that the leaf at the index is part of the tree with the given root
+pub fn verify(env: &Env, proof: &AssignmentProof) -> Result<bool, Error> {
+    if proof.siblings.len() != DEPTH {
+        return Ok(false);
+    }
+    let (mut node, mut index) = (proof.leaf.clone(), proof.index);
+    for sibling in proof.siblings.iter() {
+        let sibling = sibling.or_invalid()?;
+        node = if index & 1 == 1 { hash_nodes(env, &sibling, &node) } else { hash_nodes(env, &node, &sibling) };
+        index /= 2;
+    }
+    Ok(node == proof.root)
+}
+
+/// the stored node at the level and index - the hash of an empty subtree on the level if none is stored
+fn node(env: &Env, level: u32, index: u32, zero_node: &BytesN<32>) -> Result<BytesN<32>, Error> {
+    env.storage()
+        .get((NODES, level, index))
+        .unwrap_or_else(|| Ok(zero_node.clone()))
+        .or_invalid()
+}
+
+/// stores the node at the level and index - nodes of empty subtrees are removed instead
+fn store(env: &Env, level: u32, index: u32, node: &BytesN<32>, zero_node: &BytesN<32>) {
+    if node == zero_node {
+        env.storage().remove((NODES, level, index));
+    } else {
+        env.storage().set((NODES, level, index), node);
+    }
+}
+
+/// the hash of an empty subtree of the given height - the empty leaf being all zeros
+fn zero(env: &Env, height: u32) -> BytesN<32> {
+    let mut node = BytesN::from_array(env, &[0; 32]);
+    for _ in 0..height {
+        node = hash_nodes(env, &node, &node);
+    }
+    node
+}
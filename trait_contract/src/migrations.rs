@@ -0,0 +1,130 @@
+use soroban_sdk::{map, symbol, Address, BytesN, Env, Map, Symbol, Vec};
+use crate::types::*;
+use crate::{Stored, TraitContract, ADMIN, ASSIGNED, IS_FINAL, ISSUED, OWNERS, PICKS, TRAITS};
+
+/// storage layout written by the current version of the contract
+pub const CURRENT_VERSION: u32 = 2;
+
+/// the version of storage written before the layout was versioned
+pub const UNVERSIONED: u32 = 1;
+
+/// ed25519 key of the account that may claim admin when migrating an unversioned contract
+///
+/// Unversioned contracts had no admin. The key is fixed when building the contract from the hex encoded
+/// `MIGRATION_ADMIN` environment variable - built without it, no one can claim an unversioned contract.
+#[cfg(not(test))]
+pub const MIGRATION_ADMIN: Option<[u8; 32]> = match option_env!("MIGRATION_ADMIN") {
+    Some(hex) => Some(decode_key(hex)),
+    None => None,
+};
+
+#[cfg(test)]
+pub const MIGRATION_ADMIN: Option<[u8; 32]> = Some([0x4d; 32]);
+
+/// decodes 64 hex characters to a key - failing the build on anything else
+#[allow(dead_code)]
+const fn decode_key(hex: &str) -> [u8; 32] {
+    const fn nibble(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => panic!("MIGRATION_ADMIN must be hex encoded"),
+        }
+    }
+    let hex = hex.as_bytes();
+    if hex.len() != 64 {
+        panic!("MIGRATION_ADMIN must be a 32 byte key");
+    }
+    let mut key = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        key[i] = nibble(hex[2 * i]) << 4 | nibble(hex[2 * i + 1]);
+        i += 1;
+    }
+    key
+}
+
+/// upgrades the storage from the given version to the next one
+///
+/// returns the version the storage has been upgraded to
+pub fn migrate_from(env: &Env, version: u32) -> Result<u32, Error> {
+    match version {
+        1 => migrate_v1(env)?,
+        _ => return Err(Error::UnknownVersion),
+    }
+    Ok(version + 1)
+}
+
+/// v1 => v2
+///
+/// Unversioned contracts did not take drawn options from the supply and did not record which option has been
+/// drawn for an id. Each assigned value is matched to the first option of its trait with the same value that
+/// still has options available, the option's supply is reduced and the option is recorded as picked.
+/// As drawing did not take from the supply, an option may have been drawn more often than its total. Such a
+/// value is matched to the first option with the same value regardless of its supply and recorded as an
+/// `overdrawn` event. A value without any option of the same value fails with [`Error::OptionNotFound`].
+///
+/// The amount of issued ids is taken from the assigned ids. All assigned ids are held by the admin and are
+/// committed to the assignment tree. A finalized collection commits to its provenance hash.
+fn migrate_v1(env: &Env) -> Result<(), Error> {
+    let assigned_traits: Map<BytesN<32>, Map<Symbol, TraitOptionValue>> = env.storage()
+        .get(ASSIGNED)
+        .unwrap_or_else(|| Ok(map![env]))
+        .or_invalid()?;
+    let mut asset_traits: Vec<AssetTrait> = env.storage()
+        .get(TRAITS)
+        .unwrap_or_else(|| Ok(Vec::new(env)))
+        .or_invalid()?;
+    let admin: Address = env.storage().get(ADMIN).ok_or(Error::NotAuthorized)?.or_invalid()?;
+    let mut picks: Map<BytesN<32>, Map<Symbol, Symbol>> = map![env];
+    let mut owners: Map<BytesN<32>, Address> = map![env];
+
+    for entry in assigned_traits.iter() {
+        let (id, selected_options) = entry.or_invalid()?;
+        let mut picked = map![env];
+        for i in 0..asset_traits.len() {
+            let mut current_trait = asset_traits.get_unchecked(i).or_invalid()?;
+            let value = match selected_options.get(current_trait.name.clone()) {
+                Some(value) => value.or_invalid()?,
+                None => continue,
+            };
+            let (mut available, mut exhausted) = (None, None);
+            for j in 0..current_trait.options.len() {
+                let option = current_trait.options.get_unchecked(j).or_invalid()?;
+                if option.value != value {
+                    continue;
+                }
+                if option.available > 0 {
+                    available = Some((j, option));
+                    break;
+                }
+                exhausted.get_or_insert(option);
+            }
+            match (available, exhausted) {
+                (Some((j, option)), _) => {
+                    picked.set(current_trait.name.clone(), option.name.clone());
+                    current_trait.options.set(j, option.with_decremented_available()?);
+                    asset_traits.set(i, current_trait);
+                }
+                (None, Some(option)) => {
+                    picked.set(current_trait.name.clone(), option.name.clone());
+                    env.events().publish((symbol!("overdrawn"), id.clone(), current_trait.name), option.name);
+                }
+                (None, None) => return Err(Error::OptionNotFound),
+            }
+        }
+        picks.set(id.clone(), picked);
+        owners.set(id, admin.clone());
+    }
+
+    env.storage().set(TRAITS, asset_traits);
+    env.storage().set(PICKS, picks);
+    env.storage().set(OWNERS, owners);
+    env.storage().set(ISSUED, assigned_traits.len());
+    TraitContract::commit_assignments(env.clone(), assigned_traits)?;
+    if env.storage().has(IS_FINAL) {
+        TraitContract::commit_provenance(env.clone())?;
+    }
+    Ok(())
+}
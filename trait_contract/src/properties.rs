@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use crate::{get_random_number, AssetTrait, Error, TraitOptionItem, TraitOptionValue};
+    use crate::testutils::seeded_env;
+    use soroban_sdk::{bytes, symbol, vec, Env};
+
+    /// how many random cases each property is checked against
+    const CASES: u32 = 200;
+
+    /// a trait with the given amount of options - each with random totals and availability if `distributed`
+    fn random_trait(env: &Env, option_count: u32, distributed: bool) -> AssetTrait {
+        let mut options = vec![env];
+        for i in 0..option_count {
+            let mut option = TraitOptionItem::new(symbol!("option"), Some(TraitOptionValue::Numeric(i)));
+            if distributed {
+                option.total = get_random_number(env, 0, 20);
+                option.available = get_random_number(env, 0, option.total);
+            }
+            options.push_back(option);
+        }
+        AssetTrait{name: symbol!("trait_1"), desc: bytes!(env, 0xff), options}
+    }
+
+    #[test]
+    fn distribute_options_fails_or_adds_up() {
+        for case in 0..CASES {
+            let env = seeded_env(case);
+            let size = get_random_number(&env, 0, 50);
+            let option_count = get_random_number(&env, 0, 55);
+            let asset_trait = random_trait(&env, option_count, false);
+
+            match asset_trait.distribute_options(size, env.clone()) {
+                Ok(distributed) => {
+                    assert!(1 <= option_count && option_count <= size, "case {}: {} options of {}", case, option_count, size);
+                    let total: u32 = distributed.options.iter().map(|r| r.unwrap().total).sum();
+                    assert_eq!(total, size, "case {}", case);
+                    assert!(distributed.options.iter().map(|r| r.unwrap()).all(|o| o.total >= 1 && o.available == o.total), "case {}", case);
+                }
+                Err(e) => {
+                    assert_eq!(e, Error::OptionDistributionFailed, "case {}", case);
+                    assert!(option_count == 0 || option_count > size, "case {}: {} options of {}", case, option_count, size);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn expand_options_keeps_supply() {
+        for case in 0..CASES {
+            let env = seeded_env(case);
+            let size = get_random_number(&env, 1, 50);
+            let option_count = get_random_number(&env, 1, size);
+            let extra_size = get_random_number(&env, 0, 50);
+            let asset_trait = random_trait(&env, option_count, false).distribute_options(size, env.clone()).unwrap();
+
+            match asset_trait.clone().expand_options(extra_size, env.clone()) {
+                Ok(expanded) => {
+                    assert!(option_count <= extra_size, "case {}", case);
+                    let total: u32 = expanded.options.iter().map(|r| r.unwrap().total).sum();
+                    assert_eq!(total, size + extra_size, "case {}", case);
+                    for i in 0..option_count {
+                        let (before, after) = (asset_trait.options.get_unchecked(i).unwrap(), expanded.options.get_unchecked(i).unwrap());
+                        assert!(after.total > before.total, "case {}", case);
+                        assert_eq!(after.total - before.total, after.available - before.available, "case {}", case);
+                    }
+                }
+                Err(e) => {
+                    assert_eq!(e, Error::OptionDistributionFailed, "case {}", case);
+                    assert!(option_count > extra_size, "case {}", case);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pick_available_option_only_picks_available_options() {
+        for case in 0..CASES {
+            let env = seeded_env(case);
+            let asset_trait = random_trait(&env, get_random_number(&env, 0, 5), true);
+            let any_available = asset_trait.options.iter().map(|r| r.unwrap()).any(|o| o.available > 0);
+
+            match asset_trait.pick_available_option(&env).unwrap() {
+                Some(i) => assert!(asset_trait.options.get_unchecked(i).unwrap().available > 0, "case {}", case),
+                None => assert!(!any_available, "case {}", case),
+            }
+        }
+    }
+
+    #[test]
+    fn pick_available_option_except_never_picks_the_excluded_option() {
+        for case in 0..CASES {
+            let env = seeded_env(case);
+            let option_count = get_random_number(&env, 1, 5);
+            let asset_trait = random_trait(&env, option_count, true);
+            let excluded = get_random_number(&env, 0, option_count - 1);
+            let any_other_available = asset_trait.options.iter()
+                .map(|r| r.unwrap())
+                .enumerate()
+                .any(|(i, o)| i as u32 != excluded && o.available > 0);
+
+            match asset_trait.pick_available_option_except(&env, Some(excluded)).unwrap() {
+                Some(i) => {
+                    assert_ne!(i, excluded, "case {}", case);
+                    assert!(asset_trait.options.get_unchecked(i).unwrap().available > 0, "case {}", case);
+                }
+                None => assert!(!any_other_available, "case {}", case),
+            }
+        }
+    }
+
+    #[test]
+    fn pick_option_only_picks_distributed_options() {
+        for case in 0..CASES {
+            let env = seeded_env(case);
+            let asset_trait = random_trait(&env, get_random_number(&env, 0, 5), true);
+            let any_distributed = asset_trait.options.iter().map(|r| r.unwrap()).any(|o| o.total > 0);
+
+            match asset_trait.pick_option(&env).unwrap() {
+                Some(i) => assert!(asset_trait.options.get_unchecked(i).unwrap().total > 0, "case {}", case),
+                None => assert!(!any_distributed, "case {}", case),
+            }
+        }
+    }
+}
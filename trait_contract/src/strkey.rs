@@ -0,0 +1,41 @@
+use soroban_sdk::{Bytes, Env};
+
+/// version byte of an ed25519 public key ("G...")
+const VERSION_BYTE_ACCOUNT_ID: u8 = 6 << 3;
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// encodes an ed25519 public key to its strkey representation ("G...")
+///
+/// the version byte, the key and the crc16 checksum of both are base32 encoded.
+pub fn encode_account_id(env: &Env, key: &[u8; 32]) -> Bytes {
+    let mut payload = [0u8; 35];
+    payload[0] = VERSION_BYTE_ACCOUNT_ID;
+    payload[1..33].copy_from_slice(key);
+    let checksum = crc16_xmodem(&payload[..33]);
+    payload[33..].copy_from_slice(&checksum.to_le_bytes());
+
+    // 35 bytes are exactly 56 base32 characters - no padding needed
+    let mut encoded = [0u8; 56];
+    let (mut buffer, mut bits, mut i) = (0u32, 0, 0);
+    for byte in payload {
+        buffer = ((buffer << 8) | byte as u32) & 0xffff;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded[i] = ALPHABET[((buffer >> bits) & 0x1f) as usize];
+            i += 1;
+        }
+    }
+    Bytes::from_array(env, &encoded)
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
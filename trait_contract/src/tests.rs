@@ -1,24 +1,59 @@
 #[cfg(test)]
 mod tests {
-    use crate::{TraitCollection, TraitContract, TraitContractClient, AssetTrait, TraitOptionItem, TraitOptionValue};
-    use crate::{ASSIGNED, TRAITS};
-    use soroban_sdk::{bytes, symbol, testutils, vec, BytesN, Env, Map, Symbol, Vec};
+    use crate::{TraitCollection, TraitContract, TraitContractClient, AssetDescriptor, AssignmentProof, AssetTrait, BreedConfig, Error, Fee, MintSchedule, NewOption, Phase, Problem, RerollConfig, TraitOptionItem, TraitOptionValue};
+    use crate::{get_random_number, merkle, migrations, strkey, ADMIN, ASSIGNED, COLLECTION, ISSUED, LEAF_CNT, OWNERS, PICKS, TRAITS, VERSION};
+    use soroban_auth::{Identifier, Signature};
+    use soroban_sdk::{bytes, contractimpl, map, symbol, testutils, vec, AccountId, Address, Bytes, BytesN, Env, Map, Symbol, Vec};
+    use soroban_sdk::serde::{Deserialize, Serialize};
+    use soroban_sdk::testutils::{Accounts, Ledger, LedgerInfo};
 
     fn get_client() -> TraitContractClient {
         let env = Env::default();
+        // the source account initializing the contract becomes its admin
+        env.set_source_account(&env.accounts().generate());
         let cid = env.register_contract(None, TraitContract);
         TraitContractClient::new(&env, &cid)
     }
 
+    /// a token recording the transfers charged by the contract instead of moving balances
+    pub struct MockToken;
+
+    #[contractimpl]
+    impl MockToken {
+        pub fn xfer_from(env: Env, _spender: Signature, _nonce: i128, from: Identifier, to: Identifier, amount: i128) {
+            let mut transfers = env.storage().get(symbol!("xfers")).unwrap_or_else(|| Ok(vec![&env])).unwrap();
+            transfers.push_back((from, to, amount));
+            env.storage().set(symbol!("xfers"), transfers);
+        }
+    }
+
+    /// registers a [`MockToken`] returning its contract-id
+    fn mock_token(env: &Env) -> BytesN<32> {
+        env.register_contract(None, MockToken)
+    }
+
+    /// the transfers recorded by the [`MockToken`]
+    fn transfers(env: &Env, token: &BytesN<32>) -> Vec<(Identifier, Identifier, i128)> {
+        env.as_contract(token, || env.storage().get(symbol!("xfers")).unwrap_or_else(|| Ok(vec![env])))
+            .unwrap()
+    }
+
+    /// the admin of an initialized contract - being the source account set up by [`get_client`]
+    fn admin(client: &TraitContractClient) -> Address {
+        let env = &client.env;
+        env.as_contract(&client.contract_id, || env.storage().get_unchecked::<_, Address>(ADMIN))
+            .unwrap()
+    }
+
     #[test]
     #[should_panic(expected = "Status(ContractError(1))")] // Error::AlreadyInitialized
     fn init() {
         let client = get_client();
         let env = &client.env;
         // "test".hex => 74657374
-        assert_eq!(client.init(&bytes!(env, 0x74657374), &10), TraitCollection{name: bytes!(env, 0x74657374), size: 10});
+        assert_eq!(client.init(&bytes!(env, 0x74657374), &10, &RerollConfig::default()), TraitCollection{name: bytes!(env, 0x74657374), size: 10});
         // "other".hex => 6f74686572
-        client.init(&bytes!(env, 0x6f74686572), &1);
+        client.init(&bytes!(env, 0x6f74686572), &1, &RerollConfig::default());
     }
 
     #[test]
@@ -33,10 +68,145 @@ mod tests {
         let client = get_client();
         let env = &client.env;
         // "test".hex => 74657374
-        client.init(&bytes!(env, 0x74657374), &1);
+        client.init(&bytes!(env, 0x74657374), &1, &RerollConfig::default());
+        client.set_empty(&true);
+        assert_eq!(client.preflight(), vec![env]);
         assert!(client.finalize());
     }
 
+    #[test]
+    #[should_panic(expected = "Status(ContractError(30))")] // Error::CollectionNotReady
+    fn finalize_without_traits() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+        client.finalize();
+    }
+
+    #[test]
+    #[should_panic(expected = "Status(ContractError(4))")] // Error::TraitNotReady
+    fn finalize_trait_without_options() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        client.finalize();
+    }
+
+    #[test]
+    #[should_panic(expected = "Status(ContractError(10))")] // Error::NotAuthorized
+    fn set_empty_unauthorized() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+        client.with_source_account(&env.accounts().generate()).set_empty(&true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Status(ContractError(37))")] // Error::AlreadyFinalized
+    fn set_empty_after_finalize() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+        client.set_empty(&true);
+        client.finalize();
+        client.set_empty(&false);
+    }
+
+    #[test]
+    fn preflight() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(env, 0xff), &0, &RerollConfig::default());
+        assert_eq!(client.preflight(), vec![env, Problem::SizeZero, Problem::NoTraits]);
+
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        client.add_trait(&symbol!(""), &bytes!(env, 0xff));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        assert_eq!(client.preflight(), vec![
+            env,
+            Problem::SizeZero,
+            Problem::TooManyOptions(symbol!("trait_1"), 1),
+            Problem::EmptyName(1),
+            Problem::NoOptions(symbol!("")),
+        ]);
+    }
+
+    #[test]
+    fn preflight_with_totals() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(env, 0xff), &3, &RerollConfig::default());
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        // leaving no option for option_2
+        client.set_total(&symbol!("trait_1"), &symbol!("option_1"), &3);
+        assert_eq!(client.preflight(), vec![env, Problem::InvalidTotals(symbol!("trait_1"), 3)]);
+        // not adding up to the collection's size
+        client.set_total(&symbol!("trait_1"), &symbol!("option_2"), &1);
+        assert_eq!(client.preflight(), vec![env, Problem::InvalidTotals(symbol!("trait_1"), 4)]);
+        client.set_total(&symbol!("trait_1"), &symbol!("option_1"), &2);
+        assert_eq!(client.preflight(), vec![env]);
+    }
+
+    #[test]
+    fn finalize_with_totals() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(env, 0xff), &10, &RerollConfig::default());
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        for option in [symbol!("option_1"), symbol!("option_2"), symbol!("option_3")] {
+            client.add_option(&symbol!("trait_1"), &option, &TraitOptionValue::None);
+        }
+        let asset_trait = client.set_total(&symbol!("trait_1"), &symbol!("option_2"), &7);
+        assert_eq!(asset_trait.options.get_unchecked(1).unwrap().total, 7);
+        assert_eq!(asset_trait.options.get_unchecked(1).unwrap().available, 0);
+        client.finalize();
+
+        let stats = client.stats().get_unchecked(0).unwrap();
+        assert_eq!(stats.options.get_unchecked(1).unwrap().total, 7);
+        assert_eq!(stats.options.get_unchecked(1).unwrap().available, 7);
+        let totals: u32 = stats.options.iter().map(|r| r.unwrap().total).sum();
+        assert_eq!(totals, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Status(ContractError(5))")] // Error::OptionDistributionFailed
+    fn finalize_with_exceeding_totals() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(env, 0xff), &2, &RerollConfig::default());
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::None);
+        client.set_total(&symbol!("trait_1"), &symbol!("option_1"), &3);
+        client.finalize();
+    }
+
+    #[test]
+    #[should_panic(expected = "Status(ContractError(12))")] // Error::OptionNotFound
+    fn set_total_of_unknown_option() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(env, 0xff), &2, &RerollConfig::default());
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        client.set_total(&symbol!("trait_1"), &symbol!("option_1"), &1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Status(ContractError(37))")] // Error::AlreadyFinalized
+    fn set_total_after_finalize() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 2, &[symbol!("option_1")]);
+        client.set_total(&symbol!("trait_1"), &symbol!("option_1"), &1);
+    }
+
     #[test]
     fn add_trait() {
         let client = get_client();
@@ -49,7 +219,7 @@ mod tests {
             options: vec![env], //, TraitOptionItem{name: symbol!("option1"), value: TOV::Numeric(100), available: 1}],
         };
 
-        client.init(&bytes!(&env, 0xff), &1);
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
         let traits = client.add_trait(&new_trait.name, &new_trait.desc);
         assert_eq!(
             traits,
@@ -62,7 +232,7 @@ mod tests {
         let client = get_client();
         let env = &client.env;
 
-        client.init(&bytes!(&env, 0xff), &10);
+        client.init(&bytes!(&env, 0xff), &10, &RerollConfig::default());
         // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
         client.add_trait(&symbol!("has_opts"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
 
@@ -107,7 +277,7 @@ mod tests {
             name: symbol!("option_1"), value: TraitOptionValue::Numeric(1), available: 0, total: 0,
         };
 
-        client.init(&bytes!(&env, 0xff), &1);
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
         let res = client.add_option(&symbol!("has_opts"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
         assert_eq!(
             res.options,
@@ -121,7 +291,7 @@ mod tests {
         let client = get_client();
         let env = &client.env;
 
-        client.init(&bytes!(&env, 0xff), &10);
+        client.init(&bytes!(&env, 0xff), &10, &RerollConfig::default());
         // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
         client.add_trait(&symbol!("has_opts"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
 
@@ -134,7 +304,7 @@ mod tests {
         let client = get_client();
         let env = &client.env;
 
-        client.init(&bytes!(&env, 0xff), &10);
+        client.init(&bytes!(&env, 0xff), &10, &RerollConfig::default());
         // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
         client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
         client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
@@ -151,7 +321,7 @@ mod tests {
 
         for _ in 0..10 {
             let random_bytes = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
-            _ = client.draw(&random_bytes)
+            _ = client.draw(&admin(&client), &random_bytes)
         }
     }
 
@@ -161,7 +331,7 @@ mod tests {
         let client = get_client();
         let env = &client.env;
 
-        client.init(&bytes!(&env, 0xff), &1);
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
         // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
         client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
         client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
@@ -170,7 +340,7 @@ mod tests {
 
         for _ in 0..=1 {
             let random_bytes = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
-            _ = client.draw(&random_bytes)
+            _ = client.draw(&admin(&client), &random_bytes)
         }
     }
 
@@ -179,7 +349,7 @@ mod tests {
         let client = get_client();
         let env = &client.env;
 
-        client.init(&bytes!(&env, 0xff), &2);
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
         // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
         client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
         client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
@@ -188,7 +358,7 @@ mod tests {
         client.finalize();
         let draw_id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
         for _ in 0..5 {
-            _ = client.draw(&draw_id)
+            _ = client.draw(&admin(&client), &draw_id)
         }
 
         let assigned_traits = env
@@ -198,4 +368,1607 @@ mod tests {
         assert_eq!(assigned_traits.len(), 1);
         assert!(assigned_traits.contains_key(draw_id));
     }
+
+    #[test]
+    fn draw_many() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &3, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.finalize();
+
+        let known_id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let known_options = client.draw(&admin(&client), &known_id);
+
+        let new_ids = vec![
+            &env,
+            known_id.clone(),
+            <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>,
+            <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>,
+        ];
+        let drawn = client.draw_many(&admin(&client), &new_ids);
+        assert_eq!(drawn.len(), 3);
+        assert_eq!(drawn.get_unchecked(known_id).unwrap(), known_options);
+
+        let assigned_traits = env
+            .as_contract(&client.contract_id, || env.storage().get_unchecked::<_, Map<BytesN<32>, Map<Symbol, TraitOptionValue>>>(ASSIGNED))
+            .unwrap();
+        assert_eq!(assigned_traits, drawn);
+
+        let finalized_trait = env
+            .as_contract(&client.contract_id, || env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS))
+            .unwrap()
+            .get_unchecked(0)
+            .unwrap();
+        for o in finalized_trait.options {
+            assert_eq!(o.unwrap().available, 0);
+        }
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(8))" )] // Error::NoTraitsLeft
+    fn draw_many_over_limit() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        let mut ids = vec![&env];
+        for _ in 0..3 {
+            ids.push_back(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>);
+        }
+        client.draw_many(&admin(&client), &ids);
+    }
+
+    #[test]
+    fn release() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        let burned_id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let drawn = client.draw(&admin(&client), &burned_id);
+        assert_eq!(client.release(&burned_id), drawn);
+
+        let (assigned_traits, issued, asset_traits) = env.as_contract(&client.contract_id, || (
+            env.storage().get_unchecked::<_, Map<BytesN<32>, Map<Symbol, TraitOptionValue>>>(ASSIGNED).unwrap(),
+            env.storage().get_unchecked::<_, u32>(ISSUED).unwrap(),
+            env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS).unwrap(),
+        ));
+        assert!(!assigned_traits.contains_key(burned_id));
+        assert_eq!(issued, 0);
+        assert_eq!(asset_traits.get_unchecked(0).unwrap().options.get_unchecked(0).unwrap().available, 1);
+
+        // the released option is back in circulation
+        let new_id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        assert_eq!(client.draw(&admin(&client), &new_id), drawn);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(10))" )] // Error::NotAuthorized
+    fn release_unauthorized() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &id);
+        client.with_source_account(&env.accounts().generate()).release(&id);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(11))" )] // Error::NotAssigned
+    fn release_unknown_id() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        client.release(&(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    fn reroll() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig{limit: 1, fee: Fee::None});
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.finalize();
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let drawn = client.draw(&admin(&client), &id).get_unchecked(symbol!("trait_1")).unwrap();
+        let rerolled = client.reroll(&id, &symbol!("trait_1")).get_unchecked(symbol!("trait_1")).unwrap();
+        assert_ne!(drawn, rerolled);
+
+        let asset_trait = env
+            .as_contract(&client.contract_id, || env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS))
+            .unwrap()
+            .get_unchecked(0)
+            .unwrap();
+        for o in asset_trait.options.iter().map(|r| r.unwrap()) {
+            assert_eq!(o.available, if o.value == rerolled { 0 } else { 1 });
+        }
+    }
+
+    #[test]
+    fn reroll_with_fee() {
+        let client = get_client();
+        let env = &client.env;
+        let token = mock_token(env);
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig{limit: 1, fee: Fee::Token(token.clone(), 25)});
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.finalize();
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &id);
+        assert_eq!(transfers(env, &token).len(), 0);
+
+        client.reroll(&id, &symbol!("trait_1"));
+        let payer = match admin(&client) {
+            Address::Account(account_id) => Identifier::Account(account_id),
+            Address::Contract(contract_id) => Identifier::Contract(contract_id),
+        };
+        assert_eq!(transfers(env, &token), vec![env, (payer, Identifier::Contract(client.contract_id.clone()), 25)]);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(14))" )] // Error::RerollLimitReached
+    fn reroll_over_limit() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &3, &RerollConfig{limit: 1, fee: Fee::None});
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.finalize();
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &id);
+        client.reroll(&id, &symbol!("trait_1"));
+        client.reroll(&id, &symbol!("trait_1"));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(15))" )] // Error::NoAlternativeOption
+    fn reroll_without_alternative() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig{limit: 1, fee: Fee::None});
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &id);
+        client.reroll(&id, &symbol!("trait_1"));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(10))" )] // Error::NotAuthorized
+    fn reroll_by_other_than_holder() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig{limit: 1, fee: Fee::None});
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.finalize();
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &id);
+        client.with_source_account(&env.accounts().generate()).reroll(&id, &symbol!("trait_1"));
+    }
+
+    #[test]
+    fn swap_trait() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.add_trait(&symbol!("trait_2"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_2"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        let (holder_a, holder_b) = (env.accounts().generate(), env.accounts().generate());
+        let id_a = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let id_b = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let drawn_a = client.with_source_account(&holder_a).draw(&Address::Account(holder_a.clone()), &id_a);
+        let drawn_b = client.with_source_account(&holder_b).draw(&Address::Account(holder_b.clone()), &id_b);
+
+        client.with_source_account(&holder_b).approve(&id_b, &id_a, &symbol!("trait_1"));
+        let swapped_a = client.with_source_account(&holder_a).swap_trait(&id_a, &id_b, &symbol!("trait_1"));
+
+        assert_eq!(swapped_a.get_unchecked(symbol!("trait_1")).unwrap(), drawn_b.get_unchecked(symbol!("trait_1")).unwrap());
+        assert_eq!(swapped_a.get_unchecked(symbol!("trait_2")).unwrap(), drawn_a.get_unchecked(symbol!("trait_2")).unwrap());
+        let swapped_b = client.draw(&admin(&client), &id_b);
+        assert_eq!(swapped_b.get_unchecked(symbol!("trait_1")).unwrap(), drawn_a.get_unchecked(symbol!("trait_1")).unwrap());
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(16))" )] // Error::SwapNotApproved
+    fn swap_trait_without_approval() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        let (holder_a, holder_b) = (env.accounts().generate(), env.accounts().generate());
+        let id_a = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let id_b = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.with_source_account(&holder_a).draw(&Address::Account(holder_a.clone()), &id_a);
+        client.with_source_account(&holder_b).draw(&Address::Account(holder_b.clone()), &id_b);
+
+        client.with_source_account(&holder_a).swap_trait(&id_a, &id_b, &symbol!("trait_1"));
+    }
+
+    #[test]
+    fn breed() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.finalize();
+        client.set_breed(&BreedConfig{cap: 5, mutation_bps: 0});
+
+        let parent_a = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let parent_b = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let parent_options = vec![
+            &env,
+            client.draw(&admin(&client), &parent_a).get_unchecked(symbol!("trait_1")).unwrap(),
+            client.draw(&admin(&client), &parent_b).get_unchecked(symbol!("trait_1")).unwrap(),
+        ];
+
+        // breeding neither counts against the collection size nor the option supply
+        for _ in 0..5 {
+            let child_id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+            let child = client.breed(&parent_a, &parent_b, &child_id);
+            assert!(parent_options.contains(child.get_unchecked(symbol!("trait_1")).unwrap()));
+            assert_eq!(client.draw(&admin(&client), &child_id), child);
+        }
+        let (assigned_traits, issued) = env.as_contract(&client.contract_id, || (
+            env.storage().get_unchecked::<_, Map<BytesN<32>, Map<Symbol, TraitOptionValue>>>(ASSIGNED).unwrap(),
+            env.storage().get_unchecked::<_, u32>(ISSUED).unwrap(),
+        ));
+        assert_eq!(assigned_traits.len(), 7);
+        assert_eq!(issued, 2);
+    }
+
+    #[test]
+    fn breed_with_mutation() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &3, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_3"), &TraitOptionValue::Numeric(3));
+        client.finalize();
+        client.set_breed(&BreedConfig{cap: 20, mutation_bps: 10_000});
+
+        let parent_a = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let parent_b = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &parent_a);
+        client.draw(&admin(&client), &parent_b);
+
+        let mut mutated = false;
+        for _ in 0..20 {
+            let child_id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+            let child = client.breed(&parent_a, &parent_b, &child_id);
+            let value = child.get_unchecked(symbol!("trait_1")).unwrap();
+            mutated |= value != client.draw(&admin(&client), &parent_a).get_unchecked(symbol!("trait_1")).unwrap()
+                && value != client.draw(&admin(&client), &parent_b).get_unchecked(symbol!("trait_1")).unwrap();
+        }
+        assert!(mutated);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(33))" )] // Error::SameParents
+    fn breed_from_same_parents() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 2, &[symbol!("option_1")]);
+        client.set_breed(&BreedConfig{cap: 1, mutation_bps: 0});
+
+        let parent = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &parent);
+        client.breed(&parent, &parent, &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(34))" )] // Error::InvalidBreedConfig
+    fn set_breed_over_certain_mutation() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+        client.set_breed(&BreedConfig{cap: 1, mutation_bps: 10_001});
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(17))" )] // Error::BreedingCapReached
+    fn breed_over_cap() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+        client.set_breed(&BreedConfig{cap: 1, mutation_bps: 0});
+
+        let parent_a = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let parent_b = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &parent_a);
+        client.draw(&admin(&client), &parent_b);
+        for _ in 0..2 {
+            client.breed(&parent_a, &parent_b, &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+        }
+    }
+
+    #[test]
+    fn add_and_draw_value_kinds() {
+        let client = get_client();
+        let env = &client.env;
+
+        let values = vec![
+            &env,
+            TraitOptionValue::None,
+            TraitOptionValue::Numeric(1),
+            // "green".hex => 677265656e
+            TraitOptionValue::Characters(bytes!(&env, 0x677265656e)),
+            TraitOptionValue::Signed(-5),
+            TraitOptionValue::Boolean(true),
+            TraitOptionValue::Decimal(-12345, 2),
+            TraitOptionValue::Color(0x00ff7f),
+            TraitOptionValue::ContentHash(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>),
+        ];
+        let option_names = vec![
+            &env,
+            symbol!("none"), symbol!("numeric"), symbol!("chars"), symbol!("signed"),
+            symbol!("boolean"), symbol!("decimal"), symbol!("color"), symbol!("hash"),
+        ];
+
+        client.init(&bytes!(&env, 0xff), &values.len(), &RerollConfig::default());
+        for i in 0..values.len() {
+            // "kind".hex => 6b696e64
+            let trait_name = option_names.get_unchecked(i).unwrap();
+            client.add_trait(&trait_name, &bytes!(env, 0x6b696e64));
+            let added = client.add_option(&trait_name, &trait_name, &values.get_unchecked(i).unwrap());
+            assert_eq!(added.options.get_unchecked(0).unwrap().value, values.get_unchecked(i).unwrap());
+        }
+        client.finalize();
+
+        let drawn = client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+        for i in 0..values.len() {
+            assert_eq!(drawn.get_unchecked(option_names.get_unchecked(i).unwrap()).unwrap(), values.get_unchecked(i).unwrap());
+        }
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(20))" )] // Error::InvalidOptionValue
+    fn add_invalid_color() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Color(0x1000000));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(20))" )] // Error::InvalidOptionValue
+    fn add_invalid_decimal() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Decimal(1, 19));
+    }
+
+    #[test]
+    fn draw_range() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &20, &RerollConfig::default());
+        // "strength".hex => 737472656e677468
+        client.add_trait(&symbol!("strength"), &bytes!(env, 0x737472656e677468));
+        client.add_option(&symbol!("strength"), &symbol!("strong"), &TraitOptionValue::Range(80, 100));
+        client.add_option(&symbol!("strength"), &symbol!("weak"), &TraitOptionValue::Range(1, 20));
+        client.finalize();
+
+        let mut strong = 0;
+        for _ in 0..20 {
+            let drawn = client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+            match drawn.get_unchecked(symbol!("strength")).unwrap() {
+                TraitOptionValue::Numeric(n) if (80..=100).contains(&n) => strong += 1,
+                TraitOptionValue::Numeric(n) => assert!((1..=20).contains(&n)),
+                other => panic!("range must be resolved to a number, got {:?}", other),
+            }
+        }
+
+        // supply is still counted per option
+        let asset_trait = env
+            .as_contract(&client.contract_id, || env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS))
+            .unwrap()
+            .get_unchecked(0)
+            .unwrap();
+        assert_eq!(asset_trait.options.get_unchecked(0).unwrap().total, strong);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(20))" )] // Error::InvalidOptionValue
+    fn add_inverted_range() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Range(10, 1));
+    }
+
+    #[test]
+    fn expand() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let drawn = client.draw(&admin(&client), &id);
+
+        let season = client.expand(&3, &vec![
+            &env,
+            NewOption{trait_name: symbol!("trait_1"), name: symbol!("option_2"), value: TraitOptionValue::Numeric(2)},
+        ]);
+        assert_eq!(season.number, 1);
+        assert_eq!(season.extra_size, 3);
+        assert_eq!(client.expand(&2, &vec![&env]).number, 2);
+        assert_eq!(client.seasons().len(), 2);
+
+        let (collection, asset_trait) = env.as_contract(&client.contract_id, || (
+            env.storage().get_unchecked::<_, TraitCollection>(COLLECTION).unwrap(),
+            env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS).unwrap().get_unchecked(0).unwrap(),
+        ));
+        assert_eq!(collection.size, 7);
+        let (option_1, option_2) = (
+            asset_trait.options.get_unchecked(0).unwrap(),
+            asset_trait.options.get_unchecked(1).unwrap(),
+        );
+        assert_eq!(option_1.total + option_2.total, 7);
+        assert_eq!(option_1.total - option_1.available, 1);
+        assert_eq!(option_2.total, option_2.available);
+        assert_eq!(client.draw(&admin(&client), &id), drawn);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(4))" )] // Error::TraitNotReady
+    fn expand_less_than_options() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.finalize();
+
+        client.expand(&1, &vec![&env]);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(35))" )] // Error::InvalidSize
+    fn expand_by_nothing() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 1, &[symbol!("option_1")]);
+        client.expand(&0, &vec![&env]);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(35))" )] // Error::InvalidSize
+    fn expand_beyond_max_size() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 1, &[symbol!("option_1")]);
+        client.expand(&u32::MAX, &vec![&env]);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(30))" )] // Error::CollectionNotReady
+    fn expand_without_traits() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+        client.set_empty(&true);
+        client.finalize();
+        client.expand(&1, &vec![&env]);
+    }
+
+    /// seeds the storage of an unversioned contract (v1) with a finalized collection of three ids drawn
+    ///
+    /// The keys and values are written as the unversioned contract wrote them: without an admin and with every
+    /// option still fully available. `option_2` has been drawn twice although its total is 1.
+    /// Returns the invoker - set as source account - and the drawn ids.
+    /// an account-id with the given key - replacing the key making up the last 32 bytes of a serialized account-id
+    fn account_with_key(env: &Env, key: &[u8; 32]) -> AccountId {
+        let template = env.accounts().generate().serialize(env);
+        let mut serialized = template.slice(..template.len() - 32);
+        serialized.append(&Bytes::from_array(env, key));
+        AccountId::deserialize(env, &serialized).unwrap()
+    }
+
+    fn seed_unversioned(client: &TraitContractClient) -> (Address, [BytesN<32>; 3]) {
+        let env = &client.env;
+        let invoker = account_with_key(env, &migrations::MIGRATION_ADMIN.unwrap());
+        env.set_source_account(&invoker);
+
+        let ids = [1, 2, 3].map(|i| BytesN::from_array(env, &[i; 32]));
+        env.as_contract(&client.contract_id, || {
+            env.storage().set(symbol!("collection"), TraitCollection{name: bytes!(env, 0xff), size: 4});
+            env.storage().set(symbol!("traits"), vec![
+                env,
+                AssetTrait{
+                    name: symbol!("trait_1"),
+                    desc: bytes!(env, 0xff),
+                    options: vec![
+                        env,
+                        TraitOptionItem{name: symbol!("option_1"), value: TraitOptionValue::Numeric(1), available: 3, total: 3},
+                        TraitOptionItem{name: symbol!("option_2"), value: TraitOptionValue::Numeric(2), available: 1, total: 1},
+                    ],
+                },
+            ]);
+            env.storage().set(symbol!("assigned"), map![
+                env,
+                (ids[0].clone(), map![env, (symbol!("trait_1"), TraitOptionValue::Numeric(1))]),
+                (ids[1].clone(), map![env, (symbol!("trait_1"), TraitOptionValue::Numeric(2))]),
+                (ids[2].clone(), map![env, (symbol!("trait_1"), TraitOptionValue::Numeric(2))]),
+            ]);
+            env.storage().set(symbol!("final"), true);
+        });
+        (Address::Account(invoker), ids)
+    }
+
+    #[test]
+    fn migrate_unversioned() {
+        let client = get_client();
+        let env = &client.env;
+        let (invoker, ids) = seed_unversioned(&client);
+
+        assert_eq!(client.version(), 1);
+        assert_eq!(client.migrate(), 2);
+        assert_eq!(client.version(), 2);
+        assert_eq!(admin(&client), invoker);
+        assert!(client.verify());
+
+        let (asset_trait, issued, picks, owners, version) = env.as_contract(&client.contract_id, || (
+            env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS).unwrap().get_unchecked(0).unwrap(),
+            env.storage().get_unchecked::<_, u32>(ISSUED).unwrap(),
+            env.storage().get_unchecked::<_, Map<BytesN<32>, Map<Symbol, Symbol>>>(PICKS).unwrap(),
+            env.storage().get_unchecked::<_, Map<BytesN<32>, Address>>(OWNERS).unwrap(),
+            env.storage().get_unchecked::<_, u32>(VERSION).unwrap(),
+        ));
+        assert_eq!(version, 2);
+        assert_eq!(issued, 3);
+        assert_eq!(asset_trait.options.get_unchecked(0).unwrap().available, 2);
+        assert_eq!(asset_trait.options.get_unchecked(1).unwrap().available, 0);
+        // the overdrawn option is picked nonetheless
+        for (id, option) in ids.iter().zip([symbol!("option_1"), symbol!("option_2"), symbol!("option_2")]) {
+            assert_eq!(picks.get_unchecked(id.clone()).unwrap().get_unchecked(symbol!("trait_1")).unwrap(), option);
+            assert_eq!(owners.get_unchecked(id.clone()).unwrap(), invoker);
+            let proof = client.proof(id);
+            assert_eq!(proof.leaf, merkle::hash_leaf(env, id, &client.draw(&invoker, id)));
+            assert!(merkle::verify(env, &proof).unwrap());
+        }
+
+        // only option_1 is left to be drawn
+        let drawn = client.draw(&invoker, &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+        assert_eq!(drawn.get_unchecked(symbol!("trait_1")).unwrap(), TraitOptionValue::Numeric(1));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(10))" )] // Error::NotAuthorized
+    fn migrate_unversioned_by_other_than_admin() {
+        let client = get_client();
+        let env = &client.env;
+        seed_unversioned(&client);
+        env.as_contract(&client.contract_id, || env.storage().set(ADMIN, Address::Account(env.accounts().generate())));
+        client.migrate();
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(10))" )] // Error::NotAuthorized
+    fn migrate_unversioned_by_other_than_migration_admin() {
+        let client = get_client();
+        let env = &client.env;
+        seed_unversioned(&client);
+        client.with_source_account(&env.accounts().generate()).migrate();
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(12))" )] // Error::OptionNotFound
+    fn migrate_unversioned_without_option() {
+        let client = get_client();
+        let env = &client.env;
+        let (_, ids) = seed_unversioned(&client);
+        env.as_contract(&client.contract_id, || env.storage().set(symbol!("assigned"), map![
+            env,
+            (ids[0].clone(), map![env, (symbol!("trait_1"), TraitOptionValue::Numeric(3))]),
+        ]));
+        client.migrate();
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(21))" )] // Error::MigrationRequired
+    fn draw_before_migration() {
+        let client = get_client();
+        let env = &client.env;
+        let (invoker, _) = seed_unversioned(&client);
+
+        client.draw(&invoker, &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    fn migrate_current() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        assert_eq!(client.migrate(), 2);
+    }
+
+    #[test]
+    fn pause() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        client.pause();
+        assert!(client.is_paused());
+        assert_eq!(client.version(), 2);
+        assert_eq!(client.seasons().len(), 0);
+
+        client.unpause();
+        assert!(!client.is_paused());
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(23))" )] // Error::Paused
+    fn draw_paused() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        client.pause();
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(10))" )] // Error::NotAuthorized
+    fn pause_unauthorized() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        client.with_source_account(&env.accounts().generate()).pause();
+    }
+
+    fn set_timestamp(env: &Env, timestamp: u64) {
+        env.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 1,
+            sequence_number: 10,
+            network_passphrase: Default::default(),
+            base_reserve: 10,
+        });
+    }
+
+    fn init_scheduled(client: &TraitContractClient, phases: Vec<Phase>) {
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &10, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+        client.set_sched(&MintSchedule{start: 1_000, end: 2_000, phases, max_per_address: 2});
+    }
+
+    #[test]
+    fn draw_scheduled() {
+        let client = get_client();
+        let env = &client.env;
+        init_scheduled(&client, vec![&env]);
+
+        set_timestamp(env, 1_000);
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+        set_timestamp(env, 1_999);
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(24))" )] // Error::MintNotActive
+    fn draw_before_schedule() {
+        let client = get_client();
+        let env = &client.env;
+        init_scheduled(&client, vec![&env]);
+
+        set_timestamp(env, 999);
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(24))" )] // Error::MintNotActive
+    fn draw_after_schedule() {
+        let client = get_client();
+        let env = &client.env;
+        init_scheduled(&client, vec![&env]);
+
+        set_timestamp(env, 2_000);
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    fn draw_in_phases() {
+        let client = get_client();
+        let env = &client.env;
+        let allowed = env.accounts().generate();
+        init_scheduled(&client, vec![
+            &env,
+            Phase{start: 1_000, end: 1_500, price: Fee::None, allowlist: vec![&env, Address::Account(allowed.clone())], max_per_address: 0},
+            Phase{start: 1_500, end: 2_000, price: Fee::None, allowlist: vec![&env], max_per_address: 0},
+        ]);
+
+        set_timestamp(env, 1_200);
+        client.with_source_account(&allowed).draw(&Address::Account(allowed.clone()), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+
+        // anyone may draw in the public phase
+        set_timestamp(env, 1_700);
+        let anyone = env.accounts().generate();
+        client.with_source_account(&anyone).draw(&Address::Account(anyone.clone()), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(25))" )] // Error::NotAllowlisted
+    fn draw_not_allowlisted() {
+        let client = get_client();
+        let env = &client.env;
+        let allowed = env.accounts().generate();
+        init_scheduled(&client, vec![
+            &env,
+            Phase{start: 1_000, end: 2_000, price: Fee::None, allowlist: vec![&env, Address::Account(allowed)], max_per_address: 0},
+        ]);
+
+        set_timestamp(env, 1_500);
+        let anyone = env.accounts().generate();
+        client.with_source_account(&anyone).draw(&Address::Account(anyone.clone()), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(26))" )] // Error::InvalidSchedule
+    fn phase_outside_schedule() {
+        let client = get_client();
+        let env = &client.env;
+        init_scheduled(&client, vec![
+            &env,
+            Phase{start: 1_500, end: 2_500, price: Fee::None, allowlist: vec![&env], max_per_address: 0},
+        ]);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(26))" )] // Error::InvalidSchedule
+    fn overlapping_phases() {
+        let client = get_client();
+        let env = &client.env;
+        init_scheduled(&client, vec![
+            &env,
+            Phase{start: 1_000, end: 1_600, price: Fee::None, allowlist: vec![&env], max_per_address: 0},
+            Phase{start: 1_500, end: 2_000, price: Fee::None, allowlist: vec![&env], max_per_address: 0},
+        ]);
+    }
+
+    #[test]
+    fn draw_in_paid_phase() {
+        let client = get_client();
+        let env = &client.env;
+        let token = mock_token(env);
+        init_scheduled(&client, vec![
+            &env,
+            Phase{start: 1_000, end: 2_000, price: Fee::Token(token.clone(), 30), allowlist: vec![&env], max_per_address: 0},
+        ]);
+
+        set_timestamp(env, 1_500);
+        client.draw_many(&admin(&client), &vec![
+            &env,
+            <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>,
+            <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>,
+        ]);
+        let payer = match admin(&client) {
+            Address::Account(account_id) => Identifier::Account(account_id),
+            Address::Contract(contract_id) => Identifier::Contract(contract_id),
+        };
+        assert_eq!(transfers(env, &token), vec![env, (payer, Identifier::Contract(client.contract_id.clone()), 60)]);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(27))" )] // Error::DrawLimitReached
+    fn draw_over_address_limit() {
+        let client = get_client();
+        let env = &client.env;
+        init_scheduled(&client, vec![&env]);
+
+        set_timestamp(env, 1_500);
+        for _ in 0..3 {
+            client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+        }
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(27))" )] // Error::DrawLimitReached
+    fn draw_many_over_phase_limit() {
+        let client = get_client();
+        let env = &client.env;
+        init_scheduled(&client, vec![
+            &env,
+            Phase{start: 1_000, end: 2_000, price: Fee::None, allowlist: vec![&env], max_per_address: 1},
+        ]);
+
+        set_timestamp(env, 1_500);
+        client.draw_many(&admin(&client), &vec![
+            &env,
+            <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>,
+            <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>,
+        ]);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(27))" )] // Error::DrawLimitReached
+    fn draw_limit_per_phase() {
+        let client = get_client();
+        let env = &client.env;
+        init_scheduled(&client, vec![
+            &env,
+            Phase{start: 1_000, end: 1_500, price: Fee::None, allowlist: vec![&env], max_per_address: 1},
+            Phase{start: 1_500, end: 2_000, price: Fee::None, allowlist: vec![&env], max_per_address: 2},
+        ]);
+
+        // each phase counts its own draws
+        set_timestamp(env, 1_200);
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+        set_timestamp(env, 1_700);
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+
+        // while all draws count against the schedule's maximum of 2
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    fn draw_limit_per_address() {
+        let client = get_client();
+        let env = &client.env;
+        init_scheduled(&client, vec![&env]);
+
+        set_timestamp(env, 1_500);
+        for _ in 0..2 {
+            let holder = env.accounts().generate();
+            for _ in 0..2 {
+                client.with_source_account(&holder).draw(&Address::Account(holder.clone()), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(10))" )] // Error::NotAuthorized
+    fn draw_for_other_caller() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        client.draw(&Address::Account(env.accounts().generate()), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    fn encode_account_id() {
+        let env = Env::default();
+        let mut key = [0u8; 32];
+        assert_eq!(
+            strkey::encode_account_id(&env, &key),
+            Bytes::from_slice(&env, b"GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"),
+        );
+        for i in 0..32 {
+            key[i] = i as u8;
+        }
+        assert_eq!(
+            strkey::encode_account_id(&env, &key),
+            Bytes::from_slice(&env, b"GAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB7JZX"),
+        );
+    }
+
+    #[test]
+    fn draw_asset() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &2, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        // "RUSTLING".hex => 5255535446494e47
+        let code = bytes!(&env, 0x5255535446494e47);
+        let issuer = Address::Account(env.accounts().generate());
+        let drawn = client.draw_asset(&admin(&client), &code, &issuer);
+
+        let id = client.asset_id(&code, &issuer);
+        assert_eq!(client.draw(&admin(&client), &id), drawn);
+        assert_eq!(client.asset(&id), AssetDescriptor{code: code.clone(), issuer: issuer.clone()});
+        assert_eq!(client.draw_asset(&admin(&client), &code, &issuer), drawn);
+    }
+
+    #[test]
+    fn asset_id() {
+        let client = get_client();
+        let env = &client.env;
+        let key = [
+            0x22, 0x26, 0x2c, 0x5f, 0x7d, 0xf8, 0x7e, 0x7d, 0xb6, 0x03, 0x63, 0xb9, 0x87, 0x1d, 0xd3, 0x92,
+            0x20, 0xdb, 0x3e, 0x6f, 0x78, 0x84, 0x21, 0x13, 0xde, 0x8d, 0x7d, 0xb8, 0x7f, 0x4f, 0x5d, 0x7a,
+        ];
+        assert_eq!(
+            strkey::encode_account_id(&env, &key),
+            Bytes::from_slice(&env, b"GARCMLC7PX4H47NWANR3TBY52OJCBWZ6N54IIIIT32GX3OD7J5OXU6HQ"),
+        );
+        // an account-id with the key - replacing the key making up the last 32 bytes of a serialized account-id
+        let template = env.accounts().generate().serialize(env);
+        let mut serialized = template.slice(..template.len() - 32);
+        serialized.append(&Bytes::from_array(env, &key));
+        let issuer = Address::Account(AccountId::deserialize(env, &serialized).unwrap());
+
+        // sha256("RUSTLING:GARCMLC7PX4H47NWANR3TBY52OJCBWZ6N54IIIIT32GX3OD7J5OXU6HQ")
+        assert_eq!(client.asset_id(&Bytes::from_slice(&env, b"RUSTLING"), &issuer), BytesN::from_array(env, &[
+            0x0d, 0x6d, 0x7e, 0x76, 0xe3, 0x04, 0x74, 0x8e, 0xe0, 0x31, 0x0f, 0x6a, 0x1f, 0xa9, 0x5e, 0xda,
+            0x9d, 0x49, 0xd9, 0xdc, 0xf2, 0xa6, 0xb1, 0xd6, 0x45, 0x82, 0x41, 0x3b, 0x97, 0x02, 0xf8, 0x91,
+        ]));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(28))" )] // Error::InvalidAsset
+    fn asset_id_for_invalid_code() {
+        let client = get_client();
+        let env = &client.env;
+
+        // "RUST-LING".hex => 525553542d4c494e47
+        client.asset_id(&bytes!(&env, 0x525553542d4c494e47), &Address::Account(env.accounts().generate()));
+    }
+
+    #[test]
+    fn provenance() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &3, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.finalize();
+
+        let committed = client.provenance();
+        assert!(client.verify());
+
+        // drawing does not change the configuration
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+        assert!(client.verify());
+        assert_eq!(client.provenance(), committed);
+
+        // tampering with the distribution does
+        env.as_contract(&client.contract_id, || {
+            let mut asset_traits = env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS).unwrap();
+            let mut asset_trait = asset_traits.get_unchecked(0).unwrap();
+            let option = asset_trait.options.get_unchecked(0).unwrap();
+            asset_trait.options.set(0, option.clone().with_extra_distribution(1));
+            asset_traits.set(0, asset_trait);
+            env.storage().set(TRAITS, asset_traits);
+        });
+        assert!(!client.verify());
+    }
+
+    #[test]
+    fn provenance_after_expansion() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        let committed = client.provenance();
+        client.expand(&1, &vec![&env]);
+        assert_ne!(client.provenance(), committed);
+        assert!(client.verify());
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(7))" )] // Error::NotFinalized
+    fn provenance_before_finalize() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        client.provenance();
+    }
+
+    #[test]
+    fn proof() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &5, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.finalize();
+
+        let empty_root = client.root();
+        let mut ids: Vec<BytesN<32>> = vec![&env];
+        for _ in 0..3 {
+            let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+            client.draw(&admin(&client), &id);
+            ids.push_back(id);
+        }
+        let root = client.root();
+        assert_ne!(root, empty_root);
+
+        for (index, id) in ids.iter().map(|r| r.unwrap()).enumerate() {
+            let proof = client.proof(&id);
+            let trait_set = env
+                .as_contract(&client.contract_id, || env.storage().get_unchecked::<_, Map<BytesN<32>, Map<Symbol, TraitOptionValue>>>(ASSIGNED))
+                .unwrap()
+                .get_unchecked(id.clone())
+                .unwrap();
+            assert_eq!(proof.index, index as u32);
+            assert_eq!(proof.leaf, merkle::hash_leaf(&env, &id, &trait_set));
+            assert_eq!(proof.root, root);
+            assert!(merkle::verify(&env, &proof).unwrap());
+        }
+
+        // swapped trait-sets replace the leaves of the ids
+        let id = ids.get_unchecked(0).unwrap();
+        let previous = client.proof(&id);
+        let trait_set = client.swap_trait(&id, &ids.get_unchecked(1).unwrap(), &symbol!("trait_1"));
+        let proof = client.proof(&id);
+        assert_eq!(proof.index, 0);
+        assert_eq!(proof.leaf, merkle::hash_leaf(&env, &id, &trait_set));
+        assert_ne!(proof.root, root);
+        assert!(merkle::verify(&env, &proof).unwrap());
+        assert!(!merkle::verify(&env, &AssignmentProof{root: proof.root.clone(), ..previous}).unwrap());
+
+        // proofs do not verify against a tampered trait-set
+        let tampered = merkle::hash_leaf(&env, &id, &map![&env, (symbol!("trait_1"), TraitOptionValue::Numeric(3))]);
+        assert!(!merkle::verify(&env, &AssignmentProof{leaf: tampered, ..proof}).unwrap());
+    }
+
+    #[test]
+    fn assignment_proof_after_reroll() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 2, &[symbol!("option_1"), symbol!("option_2")]);
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &id);
+        let previous = client.proof(&id);
+        let trait_set = client.reroll(&id, &symbol!("trait_1"));
+
+        let proof = client.proof(&id);
+        assert_eq!(proof.index, previous.index);
+        assert_eq!(proof.leaf, merkle::hash_leaf(&env, &id, &trait_set));
+        assert!(merkle::verify(&env, &proof).unwrap());
+        // the trait-set before rerolling does not prove against the current root anymore
+        assert_ne!(previous.leaf, proof.leaf);
+        assert!(!merkle::verify(&env, &AssignmentProof{root: client.root(), ..previous}).unwrap());
+    }
+
+    #[test]
+    fn assignment_proof_after_release() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 2, &[symbol!("option_1")]);
+
+        let empty_root = client.root();
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &id);
+        let previous = client.proof(&id);
+        client.release(&id);
+
+        // the released leaf is emptied
+        assert_eq!(client.root(), empty_root);
+        assert!(!merkle::verify(&env, &AssignmentProof{root: client.root(), ..previous.clone()}).unwrap());
+
+        // drawing the id again commits to the same leaf
+        let trait_set = client.draw(&admin(&client), &id);
+        let proof = client.proof(&id);
+        assert_eq!(proof.index, previous.index);
+        assert_eq!(proof.leaf, merkle::hash_leaf(&env, &id, &trait_set));
+        assert!(merkle::verify(&env, &proof).unwrap());
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(11))" )] // Error::NotAssigned
+    fn assignment_proof_unknown_id() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        client.proof(&(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    fn stats() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &4, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.add_trait(&symbol!("trait_2"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_2"), &symbol!("option_1"), &TraitOptionValue::Boolean(true));
+        client.finalize();
+
+        for trait_stats in client.stats().iter().map(|r| r.unwrap()) {
+            assert_eq!(trait_stats.issued, 0);
+            for option in trait_stats.options.iter().map(|r| r.unwrap()) {
+                assert_eq!((option.issued, option.available, option.percentage_bps), (0, option.total, 0));
+            }
+        }
+
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+
+        let stats = client.stats();
+        let trait_1 = stats.get_unchecked(0).unwrap();
+        assert_eq!((trait_1.name, trait_1.issued), (symbol!("trait_1"), 2));
+        let mut percentage_bps = 0;
+        for option in trait_1.options.iter().map(|r| r.unwrap()) {
+            assert_eq!(option.issued + option.available, option.total);
+            assert_eq!(option.percentage_bps, option.issued * 5_000);
+            percentage_bps += option.percentage_bps;
+        }
+        assert_eq!(percentage_bps, 10_000);
+
+        let trait_2 = stats.get_unchecked(1).unwrap();
+        let option = trait_2.options.get_unchecked(0).unwrap();
+        assert_eq!(trait_2.issued, 2);
+        assert_eq!((option.total, option.issued, option.available, option.percentage_bps), (4, 2, 2, 10_000));
+        assert_eq!(option.value, TraitOptionValue::Boolean(true));
+    }
+
+    #[test]
+    fn rarity() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &4, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_2"), &TraitOptionValue::Numeric(2));
+        client.add_trait(&symbol!("trait_2"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_2"), &symbol!("option_1"), &TraitOptionValue::Boolean(true));
+        client.finalize();
+
+        // fix the randomly distributed totals and assignments to known values
+        let (id_1, id_2, id_3) = (
+            <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>,
+            <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>,
+            <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>,
+        );
+        env.as_contract(&client.contract_id, || {
+            let mut asset_traits = env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS).unwrap();
+            let mut trait_1 = asset_traits.get_unchecked(0).unwrap();
+            trait_1.options.set(0, trait_1.options.get_unchecked(0).unwrap().with_distribution(1));
+            trait_1.options.set(1, trait_1.options.get_unchecked(1).unwrap().with_distribution(3));
+            asset_traits.set(0, trait_1);
+            env.storage().set(TRAITS, asset_traits);
+            let picked = |option: Symbol| map![env, (symbol!("trait_1"), option), (symbol!("trait_2"), symbol!("option_1"))];
+            env.storage().set(PICKS, map![
+                env,
+                (id_1.clone(), picked(symbol!("option_1"))),
+                (id_2.clone(), picked(symbol!("option_2"))),
+                (id_3.clone(), picked(symbol!("option_2"))),
+            ]);
+        });
+
+        // 4/1 + 4/4
+        assert_eq!(client.rarity(&id_1), 5_000);
+        // 4/3 + 4/4
+        assert_eq!(client.rarity(&id_2), 2_333);
+        assert_eq!(client.rarity(&id_3), 2_333);
+
+        // all 3 ids score at most 5_000
+        assert_eq!(client.rank(&id_1), 10_000);
+        // 2 of 3 ids score at most 2_333
+        assert_eq!(client.rank(&id_2), 6_666);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(11))" )] // Error::NotAssigned
+    fn rarity_of_unknown_id() {
+        let client = get_client();
+        let env = &client.env;
+
+        client.init(&bytes!(&env, 0xff), &1, &RerollConfig::default());
+        // "a trait with options".hex => 612074726169742077697468206f7074696f6e73
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0x612074726169742077697468206f7074696f6e73));
+        client.add_option(&symbol!("trait_1"), &symbol!("option_1"), &TraitOptionValue::Numeric(1));
+        client.finalize();
+
+        client.rarity(&(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    /// a finalized collection of the given size with a single trait "trait_1" holding the given options
+    fn finalize_collection(client: &TraitContractClient, size: u32, options: &[Symbol]) {
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &size, &RerollConfig{limit: 1, fee: Fee::None});
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        for (i, option) in options.iter().enumerate() {
+            client.add_option(&symbol!("trait_1"), option, &TraitOptionValue::Numeric(i as u32));
+        }
+        client.finalize();
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(2))" )] // Error::TraitExists
+    fn duplicate_trait_name() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+    }
+
+    #[test]
+    fn distribute_without_options() {
+        let env = Env::default();
+        let asset_trait = AssetTrait{name: symbol!("trait_1"), desc: bytes!(&env, 0xff), options: vec![&env]};
+        assert_eq!(asset_trait.distribute_options(1, env.clone()), Err(Error::OptionDistributionFailed));
+    }
+
+    #[test]
+    fn distribute_with_totals() {
+        let env = Env::default();
+        let asset_trait = AssetTrait{name: symbol!("trait_1"), desc: bytes!(&env, 0xff), options: vec![
+            &env,
+            TraitOptionItem::new(symbol!("option_1"), None).with_total(2),
+            TraitOptionItem::new(symbol!("option_2"), None).with_total(3),
+        ]};
+        assert_eq!(asset_trait.totals_fit(5), Ok(true));
+        assert_eq!(asset_trait.totals_fit(6), Ok(false));
+        assert_eq!(asset_trait.clone().distribute_options(6, env.clone()), Err(Error::OptionDistributionFailed));
+
+        let distributed = asset_trait.distribute_options(5, env.clone()).unwrap();
+        for (i, total) in [(0, 2), (1, 3)] {
+            let option = distributed.options.get_unchecked(i).unwrap();
+            assert_eq!((option.total, option.available), (total, total));
+        }
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(9))" )] // Error::OptionExhausted
+    fn draw_exhausted_option() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 2, &[symbol!("option_1")]);
+
+        // the supply of the option no longer covering the collection's size
+        env.as_contract(&client.contract_id, || {
+            env.storage().set(TRAITS, vec![
+                env,
+                AssetTrait{
+                    name: symbol!("trait_1"),
+                    desc: bytes!(env, 0xff),
+                    options: vec![env, TraitOptionItem::new(symbol!("option_1"), Some(TraitOptionValue::Numeric(0)))],
+                },
+            ]);
+        });
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(12))" )] // Error::OptionNotFound
+    fn rarity_without_picked_option() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 1, &[symbol!("option_1")]);
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &id);
+        env.as_contract(&client.contract_id, || {
+            env.storage().set(PICKS, map![env, (id.clone(), map![env] as Map<Symbol, Symbol>)]);
+        });
+        client.rarity(&id);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(13))" )] // Error::OptionSupplyExceeded
+    fn release_into_full_supply() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 2, &[symbol!("option_1")]);
+
+        let id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &id);
+        // the drawn option having been returned already
+        env.as_contract(&client.contract_id, || {
+            env.storage().set(TRAITS, vec![
+                env,
+                AssetTrait{
+                    name: symbol!("trait_1"),
+                    desc: bytes!(env, 0xff),
+                    options: vec![env, TraitOptionItem::new(symbol!("option_1"), Some(TraitOptionValue::Numeric(0))).with_distribution(2)],
+                },
+            ]);
+        });
+        client.release(&id);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(18))" )] // Error::AlreadyAssigned
+    fn breed_assigned_id() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 2, &[symbol!("option_1")]);
+        client.set_breed(&BreedConfig{cap: 1, mutation_bps: 0});
+
+        let parent_a = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let parent_b = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &parent_a);
+        client.draw(&admin(&client), &parent_b);
+        client.breed(&parent_a, &parent_b, &parent_a);
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(19))" )] // Error::BredId
+    fn reroll_bred_id() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 2, &[symbol!("option_1"), symbol!("option_2")]);
+        client.set_breed(&BreedConfig{cap: 1, mutation_bps: 0});
+
+        let parent_a = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let parent_b = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        let child_id = <BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>;
+        client.draw(&admin(&client), &parent_a);
+        client.draw(&admin(&client), &parent_b);
+        client.breed(&parent_a, &parent_b, &child_id);
+        client.reroll(&child_id, &symbol!("trait_1"));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(22))" )] // Error::UnknownVersion
+    fn add_trait_to_newer_version() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+
+        env.as_contract(&client.contract_id, || env.storage().set(VERSION, 99u32));
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(29))" )] // Error::MerkleTreeFull
+    fn draw_into_full_tree() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 1, &[symbol!("option_1")]);
+
+        env.as_contract(&client.contract_id, || {
+            env.storage().set(LEAF_CNT, 1u32 << merkle::DEPTH);
+        });
+        client.draw(&admin(&client), &(<BytesN<32> as testutils::BytesN<32>>::random(&env) as BytesN<32>));
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(31))" )] // Error::MissingName
+    fn add_option_without_name() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+        client.add_option(&symbol!("trait_1"), &symbol!(""), &TraitOptionValue::Numeric(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Status(ContractError(4))")] // Error::TraitNotReady
+    fn finalize_trait_without_name() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+        client.add_trait(&symbol!(""), &bytes!(env, 0xff));
+        client.finalize();
+    }
+
+    #[test]
+    #[should_panic( expected = "Status(ContractError(32))" )] // Error::InvalidStorage
+    fn add_trait_to_invalid_storage() {
+        let client = get_client();
+        let env = &client.env;
+        client.init(&bytes!(env, 0xff), &1, &RerollConfig::default());
+
+        env.as_contract(&client.contract_id, || env.storage().set(TRAITS, 7u32));
+        client.add_trait(&symbol!("trait_1"), &bytes!(env, 0xff));
+    }
+
+    #[test]
+    fn draw_takes_from_supply() {
+        let client = get_client();
+        let env = &client.env;
+        finalize_collection(&client, 7, &[symbol!("option_1"), symbol!("option_2")]);
+        let options = || env
+            .as_contract(&client.contract_id, || env.storage().get_unchecked::<_, Vec<AssetTrait>>(TRAITS))
+            .unwrap()
+            .get_unchecked(0).unwrap()
+            .options;
+        let totals = [0, 1].map(|o| options().get_unchecked(o).unwrap().total);
+
+        let mut drawn = [0u32; 2];
+        for i in 0..7u32 {
+            let mut id = [0u8; 32];
+            id[31] = i as u8;
+            let trait_set = client.draw(&admin(&client), &BytesN::from_array(env, &id));
+            match trait_set.get_unchecked(symbol!("trait_1")).unwrap() {
+                TraitOptionValue::Numeric(o) => drawn[o as usize] += 1,
+                value => panic!("unexpected value {:?}", value),
+            }
+            let available = [0, 1].map(|o| options().get_unchecked(o).unwrap().available);
+            assert_eq!(available[0] + available[1], 6 - i);
+            for o in 0..2 {
+                assert_eq!(available[o], totals[o] - drawn[o]);
+            }
+        }
+        assert_eq!(drawn, totals);
+    }
+
+    /// the former way of selecting an option: expanding every option into `total` vector-indices
+    fn expand_available_options(env: &Env, asset_trait: &AssetTrait) -> Vec<u32> {
+        let mut opts_map: Vec<u32> = vec![env];
+        for i in 0..asset_trait.options.len() {
+            let option = asset_trait.options.get_unchecked(i).unwrap();
+            if option.available == 0 {
+                continue;
+            }
+            for _ in 0..option.total {
+                opts_map.push_back(i)
+            }
+        }
+        opts_map
+    }
+
+    #[test]
+    fn draw_budget() {
+        let env = Env::default();
+        let asset_trait = AssetTrait{
+            name: symbol!("trait_1"),
+            desc: bytes!(&env, 0xff),
+            options: vec![
+                &env,
+                TraitOptionItem::new(symbol!("common"), None).with_distribution(900),
+                TraitOptionItem::new(symbol!("rare"), None).with_distribution(90),
+                TraitOptionItem::new(symbol!("legendary"), None).with_distribution(10),
+            ],
+        };
+
+        env.budget().reset_default();
+        let expanded = expand_available_options(&env, &asset_trait);
+        _ = expanded.get_unchecked(get_random_number(&env, 0, expanded.len() - 1));
+        let expanded_cost = env.budget().cpu_instruction_cost();
+
+        env.budget().reset_default();
+        assert!(asset_trait.pick_available_option(&env).unwrap().is_some());
+        let weighted_cost = env.budget().cpu_instruction_cost();
+
+        assert!(weighted_cost < expanded_cost, "weighted: {}, expanded: {}", weighted_cost, expanded_cost);
+    }
+
+    #[test]
+    fn draw_skips_exhausted_options() {
+        let env = Env::default();
+        let asset_trait = AssetTrait{
+            name: symbol!("trait_1"),
+            desc: bytes!(&env, 0xff),
+            options: vec![
+                &env,
+                TraitOptionItem{ name: symbol!("gone"), value: TraitOptionValue::None, available: 0, total: 10 },
+                TraitOptionItem{ name: symbol!("left"), value: TraitOptionValue::None, available: 1, total: 3 },
+                TraitOptionItem{ name: symbol!("empty"), value: TraitOptionValue::None, available: 0, total: 5 },
+            ],
+        };
+
+        for _ in 0..10 {
+            assert_eq!(asset_trait.pick_available_option(&env), Ok(Some(1)));
+        }
+    }
 }
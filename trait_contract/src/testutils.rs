@@ -0,0 +1,29 @@
+//! Environments for replaying draws - shared by the tests and the simulator.
+use soroban_sdk::testutils::{Accounts, Ledger, LedgerInfo};
+use soroban_sdk::{Address, Env};
+use crate::{TraitContract, TraitContractClient};
+
+/// an environment on a ledger derived from the seed
+///
+/// the random numbers drawn by the contract only depend on the ledger - so the same seed always results in the
+/// same draws and failures can be replayed by seed.
+pub fn seeded_env(seed: u32) -> Env {
+    let env = Env::default();
+    env.ledger().set(LedgerInfo {
+        timestamp: seed as u64,
+        protocol_version: 1,
+        sequence_number: seed,
+        network_passphrase: Default::default(),
+        base_reserve: 10,
+    });
+    env
+}
+
+/// the contract registered in a [`seeded_env`] along with its admin - being the source account
+pub fn seeded_client(seed: u32) -> (TraitContractClient, Address) {
+    let env = seeded_env(seed);
+    let admin = env.accounts().generate();
+    env.set_source_account(&admin);
+    let cid = env.register_contract(None, TraitContract);
+    (TraitContractClient::new(&env, &cid), Address::Account(admin))
+}
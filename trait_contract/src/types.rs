@@ -1,20 +1,86 @@
-use soroban_sdk::{bytes, contracterror, contracttype, symbol, vec, Bytes, Env, Symbol, Vec};
-use crate::get_random_number;
+use soroban_sdk::{bytes, contracterror, contracttype, symbol, vec, Address, Bytes, BytesN, Env, Symbol, Vec};
+use crate::{get_random_number, Stored};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum Error {
+    /// `init` has not been invoked yet
     NotInitialized = 0,
+    /// `init` has already been invoked
     AlreadyInitialized = 1,
+    /// a trait with the name has already been added
     TraitExists = 2,
+    /// the collection has no trait with the name
     TraitNotFound = 3,
+    /// a trait has no name or options, or more options than the collection's (extra) size - see `preflight`
     TraitNotReady = 4,
+    /// the options of a trait could not be distributed over the collection's size
     OptionDistributionFailed = 5,
+    /// the trait already has an option with the name
     OptionAlreadyExistsOnTrait = 6,
+    /// `finalize` has not been invoked yet
     NotFinalized = 7,
+    /// every trait-set of the collection has been drawn
     NoTraitsLeft = 8,
+    /// a trait has no option available to be drawn
     OptionExhausted = 9,
+    /// the invoker is not allowed to invoke the function (e.g. not the admin or not the holder of the id)
+    NotAuthorized = 10,
+    /// no trait-set has been drawn for the id
+    NotAssigned = 11,
+    /// the trait has no option with the name
+    OptionNotFound = 12,
+    /// returning an option would exceed its total
+    OptionSupplyExceeded = 13,
+    /// the id has been rerolled as often as the [`RerollConfig`] allows
+    RerollLimitReached = 14,
+    /// the trait has no other option available to reroll to
+    NoAlternativeOption = 15,
+    /// the holder of the other id has not approved the swap
+    SwapNotApproved = 16,
+    /// as many ids have been bred as the [`BreedConfig`] allows
+    BreedingCapReached = 17,
+    /// the id to be bred has already been assigned
+    AlreadyAssigned = 18,
+    /// the id has been bred and cannot be rerolled or swapped
+    BredId = 19,
+    /// the option's value is malformed (see [`TraitOptionValue::is_valid`])
+    InvalidOptionValue = 20,
+    /// the stored data needs to be migrated first
+    MigrationRequired = 21,
+    /// the stored data has been written by a newer version of the contract
+    UnknownVersion = 22,
+    /// the contract is paused
+    Paused = 23,
+    /// drawing is not allowed at the current ledger timestamp
+    MintNotActive = 24,
+    /// the caller is not on the allowlist of the active phase
+    NotAllowlisted = 25,
+    /// the schedule ends before it starts or has a phase outside of it
+    InvalidSchedule = 26,
+    /// the caller has drawn as many ids as the schedule or phase allows
+    DrawLimitReached = 27,
+    /// the asset code or issuer is malformed
+    InvalidAsset = 28,
+    /// the assignment tree has no room left for another leaf
+    MerkleTreeFull = 29,
+    /// the collection has a size of 0 or no traits - see `preflight`
+    CollectionNotReady = 30,
+    /// an empty trait or option name has been provided
+    MissingName = 31,
+    /// the stored data could not be read as the expected type
+    InvalidStorage = 32,
+    /// both parents of a child are the same id
+    SameParents = 33,
+    /// the mutation rate of the breed config exceeds 10_000 basis points
+    InvalidBreedConfig = 34,
+    /// the extra size of an expansion is 0 or would overflow the collection size
+    InvalidSize = 35,
+    /// a trait or option name of a collection definition is not a valid symbol
+    InvalidName = 36,
+    /// the collection has already been finalized
+    AlreadyFinalized = 37,
 }
 
 #[contracttype]
@@ -30,6 +96,186 @@ impl Default for TraitCollection {
     }
 }
 
+#[contracttype]
+#[derive(Clone, Default, Debug, Eq, PartialEq)]
+pub enum Fee {
+    #[default]
+    None,
+    /// amount to be paid in the token of the given token contract-id
+    Token(BytesN<32>, i128),
+}
+
+#[contracttype]
+#[derive(Clone, Default, Debug, Eq, PartialEq)]
+pub struct RerollConfig {
+    /// how often the options of a single id may be rerolled
+    pub limit: u32,
+    pub fee: Fee,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Phase {
+    /// ledger timestamp the phase starts at
+    pub start: u64,
+    /// ledger timestamp the phase ends at (excluding)
+    pub end: u64,
+    /// to be paid for each drawn id
+    pub price: Fee,
+    /// addresses allowed to draw during the phase - anyone may draw if empty
+    pub allowlist: Vec<Address>,
+    /// how many ids a single address may draw at most during the phase - unlimited within the phase if 0
+    ///
+    /// draws during the phase also count against the schedule's maximum
+    pub max_per_address: u32,
+}
+
+impl Phase {
+    pub fn is_active(self: &Self, timestamp: u64) -> bool {
+        self.start <= timestamp && timestamp < self.end
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintSchedule {
+    /// ledger timestamp drawing starts at
+    pub start: u64,
+    /// ledger timestamp drawing ends at (excluding)
+    pub end: u64,
+    pub phases: Vec<Phase>,
+    /// how many ids a single address may draw at most - unlimited if 0
+    pub max_per_address: u32,
+}
+
+impl MintSchedule {
+    /// the schedule must not end before it starts, all phases must be within the schedule and no two phases may overlap
+    pub fn is_valid(self: &Self) -> Result<bool, Error> {
+        if self.start > self.end {
+            return Ok(false);
+        }
+        for i in 0..self.phases.len() {
+            let a = self.phases.get_unchecked(i).or_invalid()?;
+            if a.start < self.start || a.start > a.end || a.end > self.end {
+                return Ok(false);
+            }
+            for j in i + 1..self.phases.len() {
+                let b = self.phases.get_unchecked(j).or_invalid()?;
+                if a.end > b.start && b.end > a.start {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Default, Debug, Eq, PartialEq)]
+pub struct BreedConfig {
+    /// how many children may be bred at most - breeding is disabled with a cap of 0
+    pub cap: u32,
+    /// chance of an inherited option to mutate into a random option in basis points (1/10_000)
+    pub mutation_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetDescriptor {
+    /// the asset code (e.g. "RUSTLING")
+    pub code: Bytes,
+    pub issuer: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NewOption {
+    pub trait_name: Symbol,
+    pub name: Symbol,
+    pub value: TraitOptionValue,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Season {
+    /// seasons are numbered starting with 1 - the initial drop being season 0
+    pub number: u32,
+    /// by how many trait-sets the collection has been expanded
+    pub extra_size: u32,
+    /// ledger timestamp of the expansion
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OptionStats {
+    pub name: Symbol,
+    pub value: TraitOptionValue,
+    pub total: u32,
+    /// how many of the option's total have been taken from the supply
+    pub issued: u32,
+    pub available: u32,
+    /// share of all options issued of the trait in basis points (1/10_000)
+    pub percentage_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TraitStats {
+    pub name: Symbol,
+    /// how many options of the trait have been taken from the supply
+    pub issued: u32,
+    pub options: Vec<OptionStats>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssignmentProof {
+    pub leaf: BytesN<32>,
+    pub index: u32,
+    /// sibling of each level from the leaf up to the root
+    pub siblings: Vec<BytesN<32>>,
+    pub root: BytesN<32>,
+}
+
+/// a reason the collection cannot be finalized yet - see `preflight`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Problem {
+    /// the collection has a size of 0
+    SizeZero,
+    /// the collection has no traits and has not been allowed to be empty
+    NoTraits,
+    /// the trait at the given index has an empty name
+    EmptyName(u32),
+    /// the trait has no options
+    NoOptions(Symbol),
+    /// the trait has more options (the given amount) than the collection's size
+    TooManyOptions(Symbol, u32),
+    /// the fixed totals of the trait's options (the given sum) cannot be distributed over the collection's size -
+    /// see [`AssetTrait::totals_fit`]
+    InvalidTotals(Symbol, u32),
+}
+
+impl Problem {
+    /// the error `finalize` fails with because of the problem
+    pub fn error(&self) -> Error {
+        match self {
+            Problem::SizeZero | Problem::NoTraits => Error::CollectionNotReady,
+            Problem::InvalidTotals(..) => Error::OptionDistributionFailed,
+            _ => Error::TraitNotReady,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapApproval {
+    /// the id the approving id will swap its option with
+    pub with: BytesN<32>,
+    pub trait_name: Symbol,
+}
+
 #[contracttype]
 #[derive(Clone, Default, Debug, Eq, PartialEq)]
 pub enum TraitOptionValue {
@@ -37,6 +283,42 @@ pub enum TraitOptionValue {
     None,
     Numeric(u32),
     Characters(Bytes),
+    /// signed numeric value (e.g. a modifier like -5)
+    Signed(i32),
+    Boolean(bool),
+    /// fixed-point decimal given as value and decimal places: `Decimal(12345, 2)` => 123.45
+    Decimal(i64, u32),
+    /// color as 0xRRGGBB
+    Color(u32),
+    /// sha256 hash of some external content (e.g. an image layer)
+    ContentHash(BytesN<32>),
+    /// any [`TraitOptionValue::Numeric`] between min and max (both inclusive) - resolved when drawn
+    Range(u32, u32),
+}
+
+impl TraitOptionValue {
+    /// the most decimal places a [`TraitOptionValue::Decimal`] can have within an [`i64`]
+    pub const MAX_DECIMALS: u32 = 18;
+
+    pub fn is_valid(self: &Self) -> bool {
+        match self {
+            TraitOptionValue::Decimal(_, decimals) => *decimals <= Self::MAX_DECIMALS,
+            TraitOptionValue::Color(rgb) => *rgb <= 0xFFFFFF,
+            TraitOptionValue::Range(min, max) => min <= max,
+            _ => true,
+        }
+    }
+
+    /// the value to be assigned when drawn
+    ///
+    /// a [`TraitOptionValue::Range`] is resolved to a random [`TraitOptionValue::Numeric`] within the range,
+    /// any other value is assigned as is.
+    pub fn resolve(self: &Self, env: &Env) -> TraitOptionValue {
+        match self {
+            TraitOptionValue::Range(min, max) => TraitOptionValue::Numeric(get_random_number(env, *min, *max)),
+            _ => self.clone(),
+        }
+    }
 }
 
 #[contracttype]
@@ -48,6 +330,12 @@ pub struct TraitOptionItem {
     pub total: u32,
 }
 
+impl Default for TraitOptionItem {
+    fn default() -> Self {
+        TraitOptionItem::new(symbol!(""), None)
+    }
+}
+
 impl TraitOptionItem {
     pub fn new(name: Symbol, value: Option<TraitOptionValue>) -> Self {
         Self{
@@ -65,6 +353,20 @@ impl TraitOptionItem {
         new_self
     }
 
+    /// the option with a fixed total to be kept when distributing - see [`AssetTrait::distribute_options`]
+    pub fn with_total(self: Self, total: u32) -> Self {
+        let mut new_self = self.clone();
+        new_self.total = total;
+        new_self
+    }
+
+    pub fn with_extra_distribution(self: Self, distribution: u32) -> Self {
+        let mut new_self = self.clone();
+        new_self.available += distribution;
+        new_self.total += distribution;
+        new_self
+    }
+
     pub fn with_decremented_available(self: Self) -> Result<Self, Error> {
         let mut new_self = self.clone();
         if new_self.available > 0 {
@@ -74,6 +376,15 @@ impl TraitOptionItem {
         return Err(Error::OptionExhausted)
     }
 
+    pub fn with_incremented_available(self: Self) -> Result<Self, Error> {
+        let mut new_self = self.clone();
+        if new_self.available < new_self.total {
+            new_self.available += 1;
+            return Ok(new_self);
+        }
+        return Err(Error::OptionSupplyExceeded)
+    }
+
 }
 
 #[contracttype]
@@ -95,6 +406,25 @@ impl Default for AssetTrait {
 }
 
 impl AssetTrait {
+    /// fixed-point scale of rarity scores - a score of 1_000 equals an inverse frequency of 1
+    pub const RARITY_SCALE: u64 = 1_000;
+
+    /// the problems preventing the trait at the given index from being finalized within a collection of the given size
+    pub fn problems(self: &Self, env: &Env, index: u32, max_options: u32) -> Result<Vec<Problem>, Error> {
+        let mut problems = vec![env];
+        if self.name == symbol!("") {
+            problems.push_back(Problem::EmptyName(index));
+        }
+        if self.options.len() == 0 {
+            problems.push_back(Problem::NoOptions(self.name));
+        } else if self.options.len() > max_options {
+            problems.push_back(Problem::TooManyOptions(self.name, self.options.len()));
+        } else if !self.totals_fit(max_options)? {
+            problems.push_back(Problem::InvalidTotals(self.name, self.fixed_total()?));
+        }
+        Ok(problems)
+    }
+
     pub fn check_is_ready(self: &Self, max_options: u32) -> bool {
         if self.name == symbol!("") {
             return false;
@@ -105,12 +435,68 @@ impl AssetTrait {
         true
     }
 
-    pub fn distribute_options(self: Self, total_options: u32, env: Env) -> Option<AssetTrait> {
-        let mut unassigned_items = self.options.len();
-        let mut assigned_options = 0;
+    pub fn option_index(self: &Self, option_name: Symbol) -> Result<Option<u32>, Error> {
+        for i in 0..self.options.len() {
+            if self.options.get_unchecked(i).or_invalid()?.name == option_name {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    /// the sum of the fixed totals of the trait's options
+    pub fn fixed_total(self: &Self) -> Result<u32, Error> {
+        let mut fixed: u32 = 0;
+        for option in self.options.iter() {
+            fixed = fixed.saturating_add(option.or_invalid()?.total);
+        }
+        Ok(fixed)
+    }
+
+    /// the amount of the trait's options without a fixed total
+    fn unfixed_count(self: &Self) -> Result<u32, Error> {
+        let mut unfixed = 0;
+        for option in self.options.iter() {
+            if option.or_invalid()?.total == 0 {
+                unfixed += 1;
+            }
+        }
+        Ok(unfixed)
+    }
+
+    /// whether the options can be distributed over the given size keeping their fixed totals
+    ///
+    /// the fixed totals must leave at least one for each option without a fixed total - or add up to the size if
+    /// every option has a fixed total.
+    pub fn totals_fit(self: &Self, size: u32) -> Result<bool, Error> {
+        let fixed = self.fixed_total()?;
+        let unfixed = self.unfixed_count()?;
+        if unfixed == 0 {
+            return Ok(fixed == size);
+        }
+        Ok(fixed <= size && unfixed <= size - fixed)
+    }
+
+    /// randomly distributes the total options over the trait's options - each option getting at least one
+    ///
+    /// options with a fixed total keep it while the rest is distributed over the other options.
+    /// fails with [`Error::OptionDistributionFailed`] if the trait has no options or the options cannot be
+    /// distributed over the total - see [`AssetTrait::totals_fit`].
+    pub fn distribute_options(self: Self, total_options: u32, env: Env) -> Result<AssetTrait, Error> {
+        if self.options.len() == 0 || !self.totals_fit(total_options)? {
+            return Err(Error::OptionDistributionFailed);
+        }
+        let mut unassigned_items = self.unfixed_count()?;
+        let mut assigned_options = self.fixed_total()?;
         let mut res = self.clone();
 
-        while unassigned_items > 0 {
+        for i in (0..self.options.len()).rev() {
+            let option = self.options.get_unchecked(i).or_invalid()?;
+            if option.total > 0 {
+                let total = option.total;
+                res.options.set(i, option.with_distribution(total));
+                continue;
+            }
             unassigned_items -= 1;
 
             let distribution: u32;
@@ -121,43 +507,153 @@ impl AssetTrait {
             }
             assigned_options += distribution;
 
-            let new_option = self.options
-                .get_unchecked(unassigned_items)
-                .unwrap()
-                .with_distribution(distribution);
-            res.options.set(unassigned_items, new_option);
+            res.options.set(i, option.with_distribution(distribution));
         }
 
         if assigned_options == total_options {
-            return Some(res);
+            return Ok(res);
         }
-        None
+        Err(Error::OptionDistributionFailed)
     }
 
-    /// gets vector-indices of available options weighted by total options
+    /// the trait as distributed - with all options being available
+    pub fn as_distributed(self: Self) -> Result<AssetTrait, Error> {
+        let mut res = self.clone();
+        for i in 0..self.options.len() {
+            let option = self.options.get_unchecked(i).or_invalid()?;
+            let total = option.total;
+            res.options.set(i, option.with_distribution(total));
+        }
+        Ok(res)
+    }
+
+    /// inverse frequency of the option within the collection: size / total in units of 1/[`AssetTrait::RARITY_SCALE`]
+    ///
+    /// returns [`None`] if the trait has no option of the given name or the option has not been distributed.
+    pub fn rarity(self: &Self, option_name: Symbol, size: u32) -> Result<Option<u64>, Error> {
+        let index = match self.option_index(option_name)? {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let option = self.options.get_unchecked(index).or_invalid()?;
+        if option.total == 0 {
+            return Ok(None);
+        }
+        Ok(Some(size as u64 * Self::RARITY_SCALE / option.total as u64))
+    }
+
+    /// the distribution of the trait's options and how many of them have been issued so far
+    pub fn stats(self: &Self, env: &Env) -> Result<TraitStats, Error> {
+        let mut issued = 0;
+        for option in self.options.iter() {
+            let option = option.or_invalid()?;
+            issued += option.total - option.available;
+        }
+        let mut options = vec![env];
+        for option in self.options.iter() {
+            let option = option.or_invalid()?;
+            let option_issued = option.total - option.available;
+            options.push_back(OptionStats {
+                name: option.name,
+                value: option.value,
+                total: option.total,
+                issued: option_issued,
+                available: option.available,
+                percentage_bps: if issued == 0 { 0 } else { (option_issued as u64 * 10_000 / issued as u64) as u32 },
+            });
+        }
+        Ok(TraitStats { name: self.name.clone(), issued, options })
+    }
+
+    /// distributes additional options over the already distributed options
+    ///
+    /// the extra options are distributed randomly the same way as on [`AssetTrait::distribute_options`] - regardless
+    /// of the options' totals - and added to each option's available and total options.
+    pub fn expand_options(self: Self, extra_options: u32, env: Env) -> Result<AssetTrait, Error> {
+        let mut undistributed = self.clone();
+        for i in 0..self.options.len() {
+            undistributed.options.set(i, self.options.get_unchecked(i).or_invalid()?.with_distribution(0));
+        }
+        let extra = undistributed.distribute_options(extra_options, env.clone())?;
+        let mut res = self.clone();
+        for i in 0..self.options.len() {
+            let added = extra.options.get_unchecked(i).or_invalid()?.total;
+            res.options.set(i, self.options.get_unchecked(i).or_invalid()?.with_extra_distribution(added));
+        }
+        Ok(res)
+    }
+
+    /// picks the vector-index of an available option weighted by its total options
     ///
     /// as long as there are options available the original probability of being randomly
     /// picked stays intact
     /// only exhausted options are skipped.
+    /// instead of expanding each option into one vector-entry per total the cumulative
+    /// weights are walked until the randomly drawn target is reached.
     ///
     /// # example
     /// AssetTrait{ options: [
     ///   TraitOptionItem{ total: 10, available: 0},
     ///   TraitOptionItem{ total: 3, available: 2},
     ///   TraitOptionItem{ total: 5, available: 1},
-    /// ]}.get_available_options -> [1, 1, 1, 2, 2, 2, 2, 2]
-    pub fn get_available_options(self: Self, env: Env) -> Vec<u32> {
-        let mut opts_map: Vec<u32> = vec![&env];
+    /// ]}.pick_available_option -> Ok(Some(1)) with a chance of 3/8, Ok(Some(2)) with a chance of 5/8
+    pub fn pick_available_option(self: &Self, env: &Env) -> Result<Option<u32>, Error> {
+        self.pick_available_option_except(env, None)
+    }
+
+    /// picks the vector-index of any option weighted by its total options - regardless of the
+    /// options still available
+    ///
+    /// returns [`None`] if none of the options has been distributed.
+    pub fn pick_option(self: &Self, env: &Env) -> Result<Option<u32>, Error> {
+        let mut weights = 0;
+        for option in self.options.iter() {
+            weights += option.or_invalid()?.total;
+        }
+        if weights == 0 {
+            return Ok(None);
+        }
+        let mut target = get_random_number(env, 0, weights - 1);
         for i in 0..self.options.len() {
-            if let Ok(option) = self.options.get_unchecked(i) {
-                if option.available == 0 {
-                    continue;
-                }
-                for _ in 0..option.total {
-                    opts_map.push_back(i)
-                }
+            let option = self.options.get_unchecked(i).or_invalid()?;
+            if target < option.total {
+                return Ok(Some(i));
+            }
+            target -= option.total;
+        }
+        Ok(None)
+    }
+
+    /// picks the vector-index of an available option like [`AssetTrait::pick_available_option`]
+    /// but never picks the excluded option
+    pub fn pick_available_option_except(self: &Self, env: &Env, excluded: Option<u32>) -> Result<Option<u32>, Error> {
+        let weights = self.available_weights(excluded)?;
+        if weights == 0 {
+            return Ok(None);
+        }
+        let mut target = get_random_number(env, 0, weights - 1);
+        for i in 0..self.options.len() {
+            let option = self.options.get_unchecked(i).or_invalid()?;
+            if option.available == 0 || excluded == Some(i) {
+                continue;
+            }
+            if target < option.total {
+                return Ok(Some(i));
+            }
+            target -= option.total;
+        }
+        Ok(None)
+    }
+
+    /// sum of the total options of all options that are not exhausted yet
+    fn available_weights(self: &Self, excluded: Option<u32>) -> Result<u32, Error> {
+        let mut weights = 0;
+        for i in 0..self.options.len() {
+            let option = self.options.get_unchecked(i).or_invalid()?;
+            if option.available > 0 && excluded != Some(i) {
+                weights += option.total;
             }
         }
-        opts_map
+        Ok(weights)
     }
 }